@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::diagnostics::syntax_diagnostics;
+use crate::documents::Document;
+use crate::lsp::{Diagnostic, DocumentUri};
+
+/// Results of the analysis of one version of a document.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub version: i32,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Analysis {
+    pub fn new(document: &Document) -> Self {
+        Analysis {
+            version: document.version,
+            diagnostics: syntax_diagnostics(document),
+        }
+    }
+}
+
+/// Analyses of the open documents, recomputed only when a document changes
+/// version.
+#[derive(Default, Debug)]
+pub struct AnalysisCache {
+    /// Copied on write when a snapshot shares it.
+    analyses: RwLock<Arc<HashMap<DocumentUri, Arc<Analysis>>>>,
+}
+
+impl AnalysisCache {
+    pub fn get(&self, document: &Document) -> Arc<Analysis> {
+        if let Some(analysis) = self.analyses.read().unwrap().get(&document.uri) {
+            if analysis.version == document.version {
+                return analysis.clone();
+            }
+        }
+        let analysis = Arc::new(Analysis::new(document));
+        Arc::make_mut(&mut self.analyses.write().unwrap()).insert(document.uri.clone(), analysis.clone());
+        analysis
+    }
+
+    pub fn invalidate(&self, uri: &str) {
+        Arc::make_mut(&mut self.analyses.write().unwrap()).remove(uri);
+    }
+
+    /// A copy of the cache, unaffected by the later changes of this one.
+    pub fn snapshot(&self) -> AnalysisCache {
+        AnalysisCache { analyses: RwLock::new(self.analyses.read().unwrap().clone()) }
+    }
+
+    /// See [`ServerState::clear_poison`](crate::server::ServerState::clear_poison).
+    pub fn clear_poison(&self) {
+        self.analyses.clear_poison();
+    }
+
+    /// Forget every analysis, e.g. when the settings change.
+    pub fn clear(&self) {
+        *self.analyses.write().unwrap() = Arc::default();
+    }
+}
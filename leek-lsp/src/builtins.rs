@@ -0,0 +1,144 @@
+//! Functions and constants provided by the game to every AI.
+//!
+//! They are described by a catalog of the LeekWars API bundled in the
+//! binary, `data/builtins.json`, which lists for each of them the versions
+//! of LeekScript it exists in.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+const CATALOG: &str = include_str!("../data/builtins.json");
+
+/// The LeekWars API.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Catalog {
+    /// Version of the API the catalog describes.
+    pub version: String,
+    /// Functions, overloaded ones appearing once per signature.
+    pub functions: Vec<Function>,
+    pub constants: Vec<Constant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Function {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub return_type: String,
+    /// Operations used by a call.
+    pub operations: u64,
+    /// Markdown documentation.
+    pub documentation: String,
+    /// Versions of LeekScript the function exists in.
+    pub versions: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// Optional parameters come after the others.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Constant {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub value: Value,
+    /// Markdown documentation.
+    pub documentation: String,
+    /// Versions of LeekScript the constant exists in.
+    pub versions: Vec<u8>,
+}
+
+impl Function {
+    /// Number of parameters which must be given.
+    pub fn required_parameters(&self) -> usize {
+        self.parameters.iter().filter(|parameter| !parameter.optional).count()
+    }
+}
+
+/// `name(first, second?)`.
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parameters: Vec<_> = self
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}{}", parameter.name, if parameter.optional { "?" } else { "" }))
+            .collect();
+        write!(f, "{}({})", self.name, parameters.join(", "))
+    }
+}
+
+/// The bundled catalog.
+pub fn catalog() -> &'static Catalog {
+    static PARSED: OnceLock<Catalog> = OnceLock::new();
+    PARSED.get_or_init(|| serde_json::from_str(CATALOG).expect("the bundled catalog of built-ins is invalid"))
+}
+
+impl Catalog {
+    /// Functions existing in the version `version` of LeekScript.
+    pub fn functions(&self, version: u8) -> impl Iterator<Item = &Function> {
+        self.functions.iter().filter(move |function| function.versions.contains(&version))
+    }
+
+    /// Signatures of the function `name`.
+    pub fn overloads<'a>(&'a self, name: &'a str, version: u8) -> impl Iterator<Item = &'a Function> {
+        self.functions(version).filter(move |function| function.name == name)
+    }
+
+    /// Constants existing in the version `version` of LeekScript.
+    pub fn constants(&self, version: u8) -> impl Iterator<Item = &Constant> {
+        self.constants.iter().filter(move |constant| constant.versions.contains(&version))
+    }
+
+    pub fn constant(&self, name: &str, version: u8) -> Option<&Constant> {
+        self.constants(version).find(|constant| constant.name == name)
+    }
+
+    pub fn is_function(&self, name: &str, version: u8) -> bool {
+        self.overloads(name, version).next().is_some()
+    }
+
+    pub fn is_builtin(&self, name: &str, version: u8) -> bool {
+        self.is_function(name, version) || self.constant(name, version).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::LATEST_VERSION;
+    use std::collections::HashSet;
+
+    #[test]
+    fn bundled_catalog() {
+        let catalog = catalog();
+        for function in &catalog.functions {
+            assert!(!function.versions.is_empty() && function.versions.iter().all(|v| (1..=LATEST_VERSION).contains(v)));
+            // Optional parameters are last.
+            assert!(function.parameters.iter().skip(function.required_parameters()).all(|parameter| parameter.optional));
+        }
+        let mut names = HashSet::new();
+        for constant in &catalog.constants {
+            assert!(names.insert(constant.name.as_str()), "`{}` is defined twice", constant.name);
+            assert!(!constant.versions.is_empty() && constant.versions.iter().all(|v| (1..=LATEST_VERSION).contains(v)));
+        }
+
+        let overloads: Vec<_> = catalog.overloads("canUseWeapon", LATEST_VERSION).map(Function::to_string).collect();
+        assert_eq!(overloads, ["canUseWeapon(entity)", "canUseWeapon(weapon, entity)"]);
+        let get_life = catalog.overloads("getLife", 1).next().unwrap();
+        assert_eq!((get_life.required_parameters(), get_life.return_type.as_str()), (0, "integer"));
+        assert_eq!(catalog.constant("WEAPON_PISTOL", 1).unwrap().value, Value::from(37));
+        assert!(catalog.is_builtin("getEntity", 2));
+        assert!(!catalog.is_builtin("getEntity", 1));
+        assert!(!catalog.is_builtin("attack", LATEST_VERSION));
+    }
+}
@@ -0,0 +1,111 @@
+//! Calls with more or fewer arguments than the function takes.
+
+use leek_semantic::{Declaration, DeclarationKind, Reference, Resolution};
+
+use super::{diagnostic, Linked, Project};
+use crate::builtins::catalog;
+use crate::documents::Document;
+use crate::lsp::{Diagnostic, Range};
+use crate::settings::LintLevel;
+
+pub(super) fn arity_diagnostics(
+    document: &Document,
+    project: &Project<'_>,
+    linked: &Linked,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // Includes that cannot be found are reported on their own.
+    let (model, included) = (&linked.model, linked.included());
+    for reference in model.references() {
+        let arguments = match reference.arguments {
+            Some(arguments) => arguments,
+            None => continue,
+        };
+        let signatures = match &reference.resolution {
+            Resolution::Declaration(id) => user_signature(model.declaration(*id)),
+            Resolution::Included { file, declaration } => included
+                .iter()
+                .find(|(uri, _)| uri == file)
+                .and_then(|(_, included)| user_signature(included.model.declaration(*declaration))),
+            Resolution::Unresolved => builtin_signatures(&reference.name, project.settings.language_version),
+        };
+        let signatures = match signatures {
+            Some(signatures) => signatures,
+            None => continue,
+        };
+        if signatures.iter().any(|signature| signature.accepts(arguments)) {
+            continue;
+        }
+        diagnostics.extend(mismatch(document, project, reference, arguments, &signatures));
+    }
+}
+
+/// Parameters of a function, the first `required` ones having no default
+/// value.
+struct Signature<'a> {
+    name: &'a str,
+    parameters: Vec<&'a str>,
+    required: usize,
+}
+
+impl Signature<'_> {
+    fn accepts(&self, arguments: usize) -> bool {
+        self.required <= arguments && arguments <= self.parameters.len()
+    }
+}
+
+impl std::fmt::Display for Signature<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let parameters: Vec<_> = self
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(i, parameter)| if i < self.required { parameter.to_string() } else { format!("{}?", parameter) })
+            .collect();
+        write!(f, "{}({})", self.name, parameters.join(", "))
+    }
+}
+
+/// Signature of the declared function. Functions stored in variables and
+/// parameters are unknown until the code runs.
+fn user_signature(declaration: &Declaration) -> Option<Vec<Signature<'_>>> {
+    match declaration.kind {
+        DeclarationKind::Function | DeclarationKind::Method => Some(vec![Signature {
+            name: &declaration.name,
+            parameters: declaration.parameters.iter().map(String::as_str).collect(),
+            required: declaration.required_parameters,
+        }]),
+        _ => None,
+    }
+}
+
+fn builtin_signatures(name: &str, version: u8) -> Option<Vec<Signature<'_>>> {
+    let signatures: Vec<_> = catalog()
+        .overloads(name, version)
+        .map(|function| Signature {
+            name: &function.name,
+            parameters: function.parameters.iter().map(|parameter| parameter.name.as_str()).collect(),
+            required: function.required_parameters(),
+        })
+        .collect();
+    Some(signatures).filter(|signatures| !signatures.is_empty())
+}
+
+fn mismatch(
+    document: &Document,
+    project: &Project<'_>,
+    reference: &Reference,
+    arguments: usize,
+    signatures: &[Signature<'_>],
+) -> Option<Diagnostic> {
+    let expected: Vec<_> = signatures.iter().map(|signature| format!("`{}`", signature)).collect();
+    let message = format!(
+        "`{}` is called with {} argument{} but expects {}",
+        reference.name,
+        arguments,
+        if arguments == 1 { "" } else { "s" },
+        expected.join(" or "),
+    );
+    let range = Range { start: document.position_at(reference.span.start), end: document.position_at(reference.span.end) };
+    diagnostic(project.settings, "arity", LintLevel::Error, range, message)
+}
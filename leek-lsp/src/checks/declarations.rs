@@ -0,0 +1,85 @@
+//! Names declared twice in a scope, and locals hiding a global.
+
+use std::collections::HashMap;
+
+use leek_semantic::{Declaration, DeclarationKind, SemanticModel};
+
+use super::{diagnostic, Linked, Project};
+use crate::documents::Document;
+use crate::lsp::{Diagnostic, DiagnosticRelatedInformation, Location, Range};
+use crate::settings::LintLevel;
+
+pub(super) fn declaration_diagnostics(
+    document: &Document,
+    project: &Project<'_>,
+    linked: &Linked,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    redeclarations(document, project, &linked.model, diagnostics);
+    shadowed_globals(document, project, linked, diagnostics);
+}
+
+fn range(document: &Document, declaration: &Declaration) -> Range {
+    Range { start: document.position_at(declaration.span.start), end: document.position_at(declaration.span.end) }
+}
+
+fn related(location: Location, message: String) -> Option<Vec<DiagnosticRelatedInformation>> {
+    Some(vec![DiagnosticRelatedInformation { location, message }])
+}
+
+/// Declarations of a name already declared in the same scope.
+fn redeclarations(document: &Document, project: &Project<'_>, model: &SemanticModel, diagnostics: &mut Vec<Diagnostic>) {
+    for scope in model.scopes() {
+        // Methods are overloaded by their number of parameters.
+        let mut first: HashMap<(&str, Option<usize>), &Declaration> = HashMap::new();
+        for declaration in scope.declarations.iter().map(|&id| model.declaration(id)) {
+            let arity = (declaration.kind == DeclarationKind::Method).then_some(declaration.parameters.len());
+            let earlier = match first.get(&(declaration.name.as_str(), arity)) {
+                Some(earlier) => *earlier,
+                None => {
+                    first.insert((&declaration.name, arity), declaration);
+                    continue;
+                }
+            };
+            let message = format!("`{}` is already declared in this scope", declaration.name);
+            let diagnostic = diagnostic(project.settings, "redeclaration", LintLevel::Error, range(document, declaration), message);
+            if let Some(mut diagnostic) = diagnostic {
+                let location = Location { uri: document.uri.clone(), range: range(document, earlier) };
+                diagnostic.related_information = related(location, format!("`{}` is first declared here", earlier.name));
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+}
+
+/// Local variables and parameters named like a global of the document or
+/// of the files it includes.
+fn shadowed_globals(document: &Document, project: &Project<'_>, linked: &Linked, diagnostics: &mut Vec<Diagnostic>) {
+    // Includes that cannot be found are reported on their own.
+    let (model, included) = (&linked.model, linked.included());
+    let mut globals: HashMap<&str, Location> = HashMap::new();
+    for (_, global) in model.exports().filter(|(_, declaration)| declaration.kind == DeclarationKind::Global) {
+        globals.entry(&global.name).or_insert_with(|| Location { uri: document.uri.clone(), range: range(document, global) });
+    }
+    for (uri, file) in included {
+        let exported = file.model.exports().filter(|(_, declaration)| declaration.kind == DeclarationKind::Global);
+        for (id, global) in exported {
+            let location = || Location { uri: uri.clone(), range: file.declaration_range(id) };
+            globals.entry(&global.name).or_insert_with(location);
+        }
+    }
+    for declaration in model.declarations() {
+        let local = matches!(declaration.kind, DeclarationKind::Variable | DeclarationKind::Parameter)
+            && declaration.scope != SemanticModel::FILE;
+        let global = match globals.get(declaration.name.as_str()) {
+            Some(global) if local => global,
+            _ => continue,
+        };
+        let message = format!("the {} `{}` shadows a global", declaration.kind.describe(), declaration.name);
+        let diagnostic = diagnostic(project.settings, "shadowing", LintLevel::Warning, range(document, declaration), message);
+        if let Some(mut diagnostic) = diagnostic {
+            diagnostic.related_information = related(global.clone(), format!("the global `{}`", declaration.name));
+            diagnostics.push(diagnostic);
+        }
+    }
+}
@@ -0,0 +1,685 @@
+//! Whole-project checks, too slow to run on every keystroke: they run when
+//! a document is opened or saved, and when the files it depends on change.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use leek_parser::{Pair, Rule};
+use leek_semantic::SemanticModel;
+use ropey::Rope;
+
+use crate::builtins::catalog;
+use crate::diagnostics::SOURCE;
+use crate::documents::Document;
+use crate::index::{IndexedFile, SymbolIndex};
+use crate::lsp::{Diagnostic, DocumentUri, Either, Position, Range, TextDocumentContentChangeEvent};
+use crate::settings::{LintLevel, Settings};
+use crate::uri;
+use crate::workspace::Workspace;
+
+mod arity;
+mod declarations;
+mod names;
+mod unused;
+
+/// What the checks need to know about the project.
+pub struct Project<'a> {
+    pub index: &'a SymbolIndex,
+    pub workspace: &'a Workspace,
+    /// Settings of the checked document.
+    pub settings: &'a Settings,
+    /// Settings of any file, those of the root it belongs to.
+    pub settings_of: &'a dyn Fn(&str) -> Settings,
+}
+
+/// Run every check on `document`, which must be indexed.
+pub fn check(document: &Document, project: &Project<'_>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    include_diagnostics(document, project, &mut diagnostics);
+    if let Some(linked) = Linked::new(document, project) {
+        names::undefined_names(document, project, &linked, &mut diagnostics);
+        arity::arity_diagnostics(document, project, &linked, &mut diagnostics);
+        declarations::declaration_diagnostics(document, project, &linked, &mut diagnostics);
+        unused::unused_declarations(document, project, &linked, &mut diagnostics);
+    }
+    ops_estimation(document, project, &mut diagnostics);
+    diagnostics
+}
+
+/// The document analysed and linked to the files it includes, shared by
+/// the checks.
+struct Linked {
+    model: SemanticModel,
+    /// Files included by the document that could be found.
+    included: Vec<(DocumentUri, Arc<IndexedFile>)>,
+    /// Whether every included file could be found: otherwise the names
+    /// the document does not declare may come from the missing ones.
+    complete: bool,
+}
+
+impl Linked {
+    /// `None` if the document does not parse.
+    fn new(document: &Document, project: &Project<'_>) -> Option<Linked> {
+        let mut model = leek_semantic::analyze(&document.text.to_string()).ok()?;
+        let (included, complete) = included_files(document, project);
+        model.link(included.iter().map(|(uri, file)| (uri.as_str(), &file.model)));
+        Some(Linked { model, included, complete })
+    }
+
+    fn included(&self) -> &[(DocumentUri, Arc<IndexedFile>)] {
+        &self.included
+    }
+}
+
+fn diagnostic(settings: &Settings, rule: &str, default: LintLevel, range: Range, message: String) -> Option<Diagnostic> {
+    Some(Diagnostic {
+        range,
+        severity: Some(settings.lint_level(rule, default).severity()?),
+        code: Some(Either::Right(rule.to_string())),
+        code_description: None,
+        source: Some(SOURCE.to_string()),
+        message,
+        tags: None,
+        related_information: None,
+        data: None,
+    })
+}
+
+/// Included files that cannot be found, and includes leading back to the
+/// document.
+fn include_diagnostics(document: &Document, project: &Project<'_>, diagnostics: &mut Vec<Diagnostic>) {
+    let file = match project.index.file(&document.uri) {
+        Some(file) => file,
+        None => return,
+    };
+    // Includes are only resolved inside of a workspace folder.
+    if project.workspace.root_of(&document.uri).is_none() {
+        return;
+    }
+    for include in &file.includes {
+        let diagnostic = match resolve(project, &document.uri, &include.path) {
+            None => diagnostic(
+                project.settings,
+                "unresolved-include",
+                LintLevel::Error,
+                include.range,
+                format!("cannot find the included file `{}`", include.path),
+            ),
+            Some(target) => {
+                let mut visited = HashSet::new();
+                match cycle(project, &document.uri, target, &mut visited) {
+                    Some(mut path) => {
+                        path.push(document.uri.clone());
+                        let names: Vec<_> = path.iter().map(|uri| uri::file_name(uri)).collect();
+                        diagnostic(
+                            project.settings,
+                            "include-cycle",
+                            LintLevel::Warning,
+                            include.range,
+                            format!("include cycle: {} includes {}", uri::file_name(&document.uri), names.join(", then ")),
+                        )
+                    }
+                    None => None,
+                }
+            }
+        };
+        diagnostics.extend(diagnostic);
+    }
+}
+
+/// Resolve an include of `from`, which may belong to another root than the
+/// checked document.
+fn resolve(project: &Project<'_>, from: &str, path: &str) -> Option<DocumentUri> {
+    project.workspace.root_of(from)?.resolve_include(from, path, &(project.settings_of)(from))
+}
+
+/// Files included by the document, directly or not, in the order their
+/// names are looked up, and whether all of them could be found.
+fn included_files(document: &Document, project: &Project<'_>) -> (Vec<(DocumentUri, Arc<IndexedFile>)>, bool) {
+    let mut visited: HashSet<DocumentUri> = HashSet::new();
+    visited.insert(document.uri.clone());
+    let file = match project.index.file(&document.uri) {
+        Some(file) => file,
+        None => return (Vec::new(), false),
+    };
+    let mut pending: Vec<_> = file.includes.iter().map(|include| (document.uri.clone(), include.path.clone())).collect();
+    let mut included = Vec::new();
+    let mut complete = true;
+    let mut next = 0;
+    while let Some((from, path)) = pending.get(next).cloned() {
+        next += 1;
+        let uri = match resolve(project, &from, &path) {
+            Some(uri) => uri,
+            None => {
+                complete = false;
+                continue;
+            }
+        };
+        if !visited.insert(uri.clone()) {
+            continue;
+        }
+        let file = match project.index.file(&uri) {
+            Some(file) => file,
+            None => {
+                complete = false;
+                continue;
+            }
+        };
+        pending.extend(file.includes.iter().map(|include| (uri.clone(), include.path.clone())));
+        included.push((uri, file));
+    }
+    (included, complete)
+}
+
+/// Path of includes from `current` back to `start`, `current` included.
+fn cycle(project: &Project<'_>, start: &str, current: DocumentUri, visited: &mut HashSet<DocumentUri>) -> Option<Vec<DocumentUri>> {
+    if current == start {
+        return Some(Vec::new());
+    }
+    if !visited.insert(current.clone()) {
+        return None;
+    }
+    let file = project.index.file(&current)?;
+    for include in &file.includes {
+        if let Some(next) = resolve(project, &current, &include.path) {
+            if let Some(mut path) = cycle(project, start, next, visited) {
+                path.insert(0, current);
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// Warn when the top-level code of the document is sure to use more
+/// operations than the budget of a turn.
+///
+/// The estimation is a lower bound: loops may not run, the cheapest branch
+/// of an `if` is taken, and functions of other files cost a single call.
+/// Built-in functions cost the operations the game charges for them.
+fn ops_estimation(document: &Document, project: &Project<'_>, diagnostics: &mut Vec<Diagnostic>) {
+    let budget = match project.settings.ops_budget {
+        Some(budget) => budget,
+        None => return,
+    };
+    let source = document.text.to_string();
+    let statements: Vec<_> = match leek_parser::parse(&source) {
+        Ok(pairs) => pairs.flat_map(Pair::into_inner).collect(),
+        Err(_) => return,
+    };
+    let mut estimator = Estimator::new(project.settings.language_version);
+    for statement in &statements {
+        estimator.declare(statement.clone());
+    }
+    let mut total = 0;
+    for statement in statements {
+        total += estimator.cost(statement.clone());
+        if total > budget {
+            let range = Range {
+                start: document.position_at(statement.as_span().start()),
+                end: document.position_at(statement.as_span().end()),
+            };
+            let message = format!("the AI uses at least {} operations by here, over the budget of {}", total, budget);
+            diagnostics.extend(diagnostic(project.settings, "ops-budget", LintLevel::Warning, range, message));
+            return;
+        }
+    }
+}
+
+/// Lower bound of the operations used to run the code.
+struct Estimator<'a> {
+    /// Version of LeekScript, which decides the built-in functions.
+    version: u8,
+    functions: HashMap<&'a str, Pair<'a, Rule>>,
+    /// Cost of the calls of each function, once known.
+    costs: HashMap<&'a str, u64>,
+    /// Functions whose cost is being computed, to stop on recursion.
+    computing: HashSet<&'a str>,
+}
+
+impl<'a> Estimator<'a> {
+    fn new(version: u8) -> Self {
+        Estimator { version, functions: HashMap::new(), costs: HashMap::new(), computing: HashSet::new() }
+    }
+
+    /// Record the functions declared in `pair`.
+    fn declare(&mut self, pair: Pair<'a, Rule>) {
+        if pair.as_rule() == Rule::Defun {
+            if let Some(name) = pair.clone().into_inner().find(|pair| pair.as_rule() == Rule::Ident) {
+                self.functions.insert(name.as_str(), pair.clone());
+            }
+        }
+        for inner in pair.into_inner() {
+            self.declare(inner);
+        }
+    }
+
+    fn cost(&mut self, pair: Pair<'a, Rule>) -> u64 {
+        match pair.as_rule() {
+            // Declaring a function or a class costs nothing, using it does.
+            Rule::Defun | Rule::Class => 0,
+            Rule::Declr | Rule::Global | Rule::Affect => 1 + self.cost_of_inner(pair),
+            Rule::Call => {
+                let mut inner = pair.into_inner();
+                let name = inner.next().map_or("", |name| name.as_str());
+                let arguments: Vec<_> = inner.flat_map(Pair::into_inner).collect();
+                let count = arguments.len();
+                let arguments: u64 = arguments.into_iter().map(|pair| self.cost(pair)).sum();
+                1 + arguments + self.call_cost(name, count)
+            }
+            Rule::IfElse => {
+                let mut inner = pair.into_inner();
+                let mut branch = inner.next().map(Pair::into_inner).into_iter().flatten();
+                let condition = branch.next().map_or(0, |pair| self.cost(pair));
+                let then = branch.next().map_or(0, |pair| self.cost(pair));
+                let otherwise = inner.next().map_or(0, |pair| self.cost_of_inner(pair));
+                1 + condition + then.min(otherwise)
+            }
+            // The body of a loop may not run.
+            Rule::While => 1 + pair.into_inner().next().map_or(0, |pair| self.cost(pair)),
+            Rule::For => 1,
+            _ => self.cost_of_inner(pair),
+        }
+    }
+
+    fn cost_of_inner(&mut self, pair: Pair<'a, Rule>) -> u64 {
+        pair.into_inner().map(|pair| self.cost(pair)).sum()
+    }
+
+    /// Operations run in the body of the function `name`, called with
+    /// `arguments` arguments.
+    fn call_cost(&mut self, name: &'a str, arguments: usize) -> u64 {
+        if let Some(cost) = self.costs.get(name) {
+            return *cost;
+        }
+        let function = match self.functions.get(name) {
+            Some(function) => function.clone(),
+            None => return self.builtin_cost(name, arguments),
+        };
+        if !self.computing.insert(name) {
+            return 0;
+        }
+        let body = function.into_inner().filter(|pair| pair.as_rule() == Rule::Prog);
+        let cost = body.map(|pair| self.cost(pair)).sum();
+        self.computing.remove(name);
+        self.costs.insert(name, cost);
+        cost
+    }
+
+    /// Operations of the cheapest overload of the built-in function `name`
+    /// taking `arguments` arguments.
+    fn builtin_cost(&self, name: &str, arguments: usize) -> u64 {
+        catalog()
+            .overloads(name, self.version)
+            .filter(|function| function.required_parameters() <= arguments && arguments <= function.parameters.len())
+            .map(|function| function.operations)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// Results of the checks, by document URI.
+#[derive(Default, Debug)]
+pub struct CheckResults {
+    results: RwLock<HashMap<DocumentUri, Arc<Vec<Diagnostic>>>>,
+}
+
+impl CheckResults {
+    /// Diagnostics of the last checks of `uri`, kept while the document is
+    /// edited until the next checks.
+    pub fn get(&self, uri: &str) -> Arc<Vec<Diagnostic>> {
+        self.results.read().unwrap().get(uri).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&self, uri: &str, diagnostics: Vec<Diagnostic>) {
+        self.results.write().unwrap().insert(uri.to_string(), Arc::new(diagnostics));
+    }
+
+    pub fn remove(&self, uri: &str) {
+        self.results.write().unwrap().remove(uri);
+    }
+
+    /// Follow the edits of `uri` until the next checks: diagnostics after
+    /// an edit move with the text, the ones an edit touches are dropped.
+    pub fn edit(&self, uri: &str, changes: &[TextDocumentContentChangeEvent]) {
+        let mut results = self.results.write().unwrap();
+        let diagnostics = match results.get_mut(uri) {
+            Some(diagnostics) => diagnostics,
+            None => return,
+        };
+        let mut edited = Vec::clone(diagnostics);
+        for change in changes {
+            let range = match change.range {
+                Some(range) => range,
+                None => {
+                    edited.clear();
+                    break;
+                }
+            };
+            edited.retain_mut(|diagnostic| {
+                let moved = match shift(diagnostic.range, range, &change.text) {
+                    Some(moved) => moved,
+                    None => return false,
+                };
+                diagnostic.range = moved;
+                let related = diagnostic.related_information.iter_mut().flatten();
+                for information in related.filter(|information| information.location.uri == uri) {
+                    match shift(information.location.range, range, &change.text) {
+                        Some(moved) => information.location.range = moved,
+                        None => return false,
+                    }
+                }
+                true
+            });
+        }
+        *diagnostics = Arc::new(edited);
+    }
+
+    /// See [`ServerState::clear_poison`](crate::server::ServerState::clear_poison).
+    pub fn clear_poison(&self) {
+        self.results.clear_poison();
+    }
+}
+
+/// Where `range` is once `edited` is replaced by `text`, or `None` if they
+/// overlap.
+fn shift(range: Range, edited: Range, text: &str) -> Option<Range> {
+    if range.end <= edited.start {
+        return Some(range);
+    }
+    if range.start < edited.end {
+        return None;
+    }
+    let text = Rope::from_str(text);
+    let lines = text.len_lines() as u32 - 1;
+    let last_line = text.line(text.len_lines() - 1).len_utf16_cu() as u32;
+    let position = |position: Position| {
+        if position.line > edited.end.line {
+            return Position { line: position.line - edited.end.line + edited.start.line + lines, ..position };
+        }
+        let start = if lines == 0 { edited.start.character } else { 0 };
+        Position {
+            line: edited.start.line + lines,
+            character: start + last_line + (position.character - edited.end.character),
+        }
+    };
+    Some(Range { start: position(range.start), end: position(range.end) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::{DiagnosticSeverity, DiagnosticTag, WorkspaceFolder};
+    use std::fs;
+
+    /// Check `main.leek` in a workspace made of `files`.
+    fn check_main(files: &[(&str, &str)], settings: &Settings) -> Vec<Diagnostic> {
+        check_folders(files, settings, &[])
+    }
+
+    /// Check `main.leek` in a workspace made of `files`, where the
+    /// directories `folders` are workspace folders of their own.
+    fn check_folders(files: &[(&str, &str)], settings: &Settings, folders: &[(&str, Settings)]) -> Vec<Diagnostic> {
+        let directory = tempfile::tempdir().unwrap();
+        let mut workspace = Workspace::default();
+        let root = uri::from_path(directory.path()).unwrap();
+        workspace.add(WorkspaceFolder { uri: root.clone(), name: "ais".to_string() });
+        let mut roots = HashMap::new();
+        for (folder, settings) in folders {
+            let uri = uri::from_path(&directory.path().join(folder)).unwrap();
+            workspace.add(WorkspaceFolder { uri: uri.clone(), name: folder.to_string() });
+            roots.insert(uri, settings.clone());
+        }
+        let index = SymbolIndex::default();
+        for (path, text) in files {
+            let path = directory.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, text).unwrap();
+            index.update_from_disk(&path);
+        }
+        workspace.get_mut(&root).unwrap().project = crate::project::Project::load(directory.path()).unwrap();
+        let uri = uri::from_path(&directory.path().join("main.leek")).unwrap();
+        let document = Document::from_text(uri.clone(), fs::read_to_string(directory.path().join("main.leek")).unwrap());
+        let settings_of = |uri: &str| {
+            let root = workspace.root_of(uri).map(|root| root.uri());
+            root.and_then(|root| roots.get(root)).unwrap_or(settings).clone()
+        };
+        check(&document, &Project { index: &index, workspace: &workspace, settings, settings_of: &settings_of })
+    }
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect()
+    }
+
+    #[test]
+    fn shift_ranges() {
+        let range = |start: (u32, u32), end: (u32, u32)| Range {
+            start: Position { line: start.0, character: start.1 },
+            end: Position { line: end.0, character: end.1 },
+        };
+        let diagnostic = range((2, 6), (2, 10));
+        assert_eq!(shift(diagnostic, range((3, 0), (3, 4)), "x"), Some(diagnostic));
+        assert_eq!(shift(diagnostic, range((2, 0), (2, 1)), "éé"), Some(range((2, 7), (2, 11))));
+        assert_eq!(shift(diagnostic, range((0, 3), (2, 2)), "a\nbc"), Some(range((1, 6), (1, 10))));
+        assert_eq!(shift(diagnostic, range((2, 8), (2, 8)), "x"), None);
+    }
+
+    #[test]
+    fn includes() {
+        let files = [
+            ("main.leek", "include(\"lib/a.leek\");\ninclude(\"missing.leek\");\n"),
+            ("lib/a.leek", "include(\"b.leek\");"),
+            ("lib/b.leek", "include(\"../main.leek\");"),
+        ];
+        let diagnostics = check_main(&files, &Settings::default());
+        assert_eq!(messages(&diagnostics), [
+            "include cycle: main.leek includes a.leek, then b.leek, then main.leek",
+            "cannot find the included file `missing.leek`",
+        ]);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::Warning));
+        assert_eq!(diagnostics[1].severity, Some(DiagnosticSeverity::Error));
+        assert_eq!(diagnostics[1].code, Some(Either::Right("unresolved-include".to_string())));
+
+        // The includes of another root follow its own include paths.
+        let files = [
+            ("main.leek", "include(\"legacy/a.leek\");\nattack(1);\n"),
+            ("legacy/a.leek", "include(\"b.leek\");\n"),
+            ("legacy/lib/b.leek", "function attack() { return 1; }\n"),
+        ];
+        let legacy = Settings { include_paths: vec!["lib".to_string()], ..Settings::default() };
+        let diagnostics = check_folders(&files, &Settings::default(), &[("legacy", legacy)]);
+        assert_eq!(messages(&diagnostics), ["`attack` is called with 1 argument but expects `attack()`"]);
+    }
+
+    #[test]
+    fn unused_declarations() {
+        let files = [
+            (
+                "main.leek",
+                "global unused_global = 1;\nglobal shared_global = 2;\nvar counter = 0;\ncounter = 1;\n\
+                 function used(a, b, _c) { var local = a; return local; }\n\
+                 function unused() { return 1; }\nfunction shared() { return 1; }\nused(1, 2, 3);\n\
+                 function walk(n) { return walk(n); }\n",
+            ),
+            ("lib/other.leek", "include(\"../main.leek\");\nshared();\ndebug(shared_global);\n"),
+            ("lib/unrelated.leek", "unused();\ndebug(unused_global);\n"),
+        ];
+        let diagnostics = check_main(&files, &Settings::default());
+        assert_eq!(messages(&diagnostics), [
+            "global `unused_global` is never used",
+            "variable `counter` is never used",
+            "parameter `b` is never used",
+            "function `unused` is never called",
+            "function `walk` is never called",
+        ]);
+        assert!(diagnostics.iter().all(|diagnostic| diagnostic.severity == Some(DiagnosticSeverity::Warning)));
+        assert!(diagnostics.iter().all(|diagnostic| diagnostic.tags == Some(vec![DiagnosticTag::Unnecessary])));
+        assert_eq!(diagnostics[3].range.start, Position { line: 5, character: 9 });
+        assert_eq!(diagnostics[2].code, Some(Either::Right("unused-parameter".to_string())));
+
+        let mut settings = Settings::default();
+        settings.lints.insert("unused-function".to_string(), LintLevel::Off);
+        settings.lints.insert("unused-parameter".to_string(), LintLevel::Off);
+        assert_eq!(messages(&check_main(&files, &settings)), [
+            "global `unused_global` is never used",
+            "variable `counter` is never used",
+        ]);
+    }
+
+    #[test]
+    fn unused_outside_of_entry_points() {
+        let files = [
+            ("leek.toml", "[project]\nentry-points = [\"bot.leek\"]\n"),
+            ("main.leek", "global life = 1;\nglobal range = 2;\n"),
+            ("bot.leek", "include(\"main.leek\");\ndebug(life);\n"),
+            ("scratch.leek", "include(\"main.leek\");\ndebug(range);\n"),
+        ];
+        assert_eq!(messages(&check_main(&files, &Settings::default())), ["global `range` is never used"]);
+
+        // Without entry points, any file including the document counts.
+        let files = [files[1], files[2], files[3]];
+        assert!(check_main(&files, &Settings::default()).is_empty());
+    }
+
+    #[test]
+    fn undefined_names() {
+        let files = [
+            ("main.leek", "include(\"lib/a.leek\");\nvar life = getLfe();\nheal(lif);\nattack(WEAPON_PISTOL, range);\nfunction f() { return life; }\nf();\nx = later;\nvar later = 1;\ndebug(life);\ndebug(later);\n"),
+            ("lib/a.leek", "include(\"b.leek\");\nfunction heal(amount) { return amount; }\n"),
+            ("lib/b.leek", "function attacks(target) { return target; }\nglobal range = 3;\n"),
+        ];
+        let diagnostics = check_main(&files, &Settings::default());
+        assert_eq!(messages(&diagnostics), [
+            "unknown function `getLfe`, did you mean `getLife`?",
+            "`lif` is not defined, did you mean `life`?",
+            "unknown function `attack`, did you mean `attacks`?",
+            "`life` is not defined",
+            "`x` is not defined",
+            "`later` is used before its declaration",
+        ]);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::Error));
+        assert_eq!(diagnostics[0].code, Some(Either::Right("undefined-function".to_string())));
+        assert_eq!(diagnostics[1].range.start, Position { line: 2, character: 5 });
+
+        let mut settings = Settings::default();
+        settings.lints.insert("undefined-variable".to_string(), LintLevel::Off);
+        assert_eq!(check_main(&files, &settings).len(), 2);
+
+        // Names may come from an include that cannot be found, unlike the
+        // ones the document declares.
+        let files = [
+            (
+                "main.leek",
+                "include(\"lib/a.leek\");\ninclude(\"missing.leek\");\nheal(1, 2);\nx = later;\nvar later = unknown;\ndebug(later);\n",
+            ),
+            ("lib/a.leek", "function heal(amount) { return amount; }\n"),
+        ];
+        assert_eq!(messages(&check_main(&files, &Settings::default())), [
+            "cannot find the included file `missing.leek`",
+            "`later` is used before its declaration",
+            "`heal` is called with 2 arguments but expects `heal(amount)`",
+        ]);
+    }
+
+    #[test]
+    fn redeclarations_and_shadowing() {
+        let files = [
+            (
+                "main.leek",
+                "include(\"lib/a.leek\");\nglobal life = 1;\nvar life = 2;\n\
+                 function attack(target, target) { var range = target; var life = range; return life; }\nattack(life, 1);\n",
+            ),
+            // The global, not the variable of the same name.
+            ("lib/a.leek", "var range = 2;\nglobal range = 3;\n"),
+        ];
+        let diagnostics = check_main(&files, &Settings::default());
+        assert_eq!(messages(&diagnostics), [
+            "`life` is already declared in this scope",
+            "`target` is already declared in this scope",
+            "the variable `range` shadows a global",
+            "the variable `life` shadows a global",
+            "global `life` is never used",
+            "parameter `target` is never used",
+        ]);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::Error));
+        assert_eq!(diagnostics[2].severity, Some(DiagnosticSeverity::Warning));
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert_eq!(related[0].message, "`life` is first declared here");
+        assert_eq!(related[0].location.range.start, Position { line: 1, character: 7 });
+        let related = diagnostics[2].related_information.as_ref().unwrap();
+        assert!(related[0].location.uri.ends_with("/lib/a.leek"));
+        assert_eq!(related[0].location.range.start, Position { line: 1, character: 7 });
+
+        let mut settings = Settings::default();
+        settings.lints.insert("redeclaration".to_string(), LintLevel::Warning);
+        settings.lints.insert("shadowing".to_string(), LintLevel::Off);
+        let diagnostics = check_main(&files, &settings);
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::Warning));
+
+        // Overloaded methods differ by their number of parameters.
+        let files = [(
+            "main.leek",
+            "class Robot {\n    attack() { return 1; }\n    attack(a) { return a; }\n    attack(b) { return b; }\n}\n\
+             debug(Robot);\n",
+        )];
+        let diagnostics = check_main(&files, &Settings::default());
+        assert_eq!(messages(&diagnostics), ["`attack` is already declared in this scope"]);
+        assert_eq!(diagnostics[0].range.start, Position { line: 3, character: 4 });
+    }
+
+    #[test]
+    fn call_arity() {
+        let files = [
+            (
+                "main.leek",
+                "include(\"lib/a.leek\");\n\
+                 function attack(target, mp = target) { return moveToward(target, mp); }\n\
+                 function apply(callback) { return callback(1, 2, 3); }\n\
+                 attack();\nattack(1);\nattack(1, 2, 3);\nheal(1);\ngetLife(1, 2);\n\
+                 canUseWeapon(1, 2);\ncanUseWeapon();\napply(attack);\n",
+            ),
+            ("lib/a.leek", "function heal() { return 1; }\n"),
+        ];
+        let diagnostics = check_main(&files, &Settings::default());
+        assert_eq!(messages(&diagnostics), [
+            "`attack` is called with 0 arguments but expects `attack(target, mp?)`",
+            "`attack` is called with 3 arguments but expects `attack(target, mp?)`",
+            "`heal` is called with 1 argument but expects `heal()`",
+            "`getLife` is called with 2 arguments but expects `getLife(entity?)`",
+            "`canUseWeapon` is called with 0 arguments but expects `canUseWeapon(entity)` or `canUseWeapon(weapon, entity)`",
+        ]);
+        assert_eq!(diagnostics[0].code, Some(Either::Right("arity".to_string())));
+        assert_eq!(diagnostics[0].range.start, Position { line: 3, character: 0 });
+    }
+
+    #[test]
+    fn operations_over_budget() {
+        let source = "function cost() { var x = 1; var y = 2; return 1; }\n\
+                      while (cost()) { cost(); cost(); }\n\
+                      var a = cost();\n\
+                      var b = 1;\n";
+        let mut quiet = Settings::default();
+        quiet.lints.insert("unused-variable".to_string(), LintLevel::Off);
+        let settings = Settings { ops_budget: Some(8), ..quiet.clone() };
+        let diagnostics = check_main(&[("main.leek", source)], &settings);
+        // The loop runs its condition only: 1 + 3, then 1 + 3 for `a`.
+        assert_eq!(messages(&diagnostics), ["the AI uses at least 9 operations by here, over the budget of 8"]);
+        assert_eq!(diagnostics[0].range.start.line, 3);
+        assert!(check_main(&[("main.leek", source)], &quiet).is_empty());
+
+        // Built-in functions cost what the game charges, in the version
+        // they exist in.
+        let source = "var path = getPath(1, 2);\nvar enemy = getEntity();\ngetLife();\n";
+        let settings = Settings { ops_budget: Some(104), ..quiet.clone() };
+        let diagnostics = check_main(&[("main.leek", source)], &settings);
+        assert_eq!(messages(&diagnostics), ["the AI uses at least 105 operations by here, over the budget of 104"]);
+        assert_eq!(diagnostics[0].range.start.line, 1);
+        let settings = Settings { language_version: 1, ..settings };
+        let diagnostics = check_main(&[("main.leek", source)], &settings);
+        assert_eq!(messages(&diagnostics), [
+            "unknown function `getEntity`",
+            "the AI uses at least 106 operations by here, over the budget of 104",
+        ]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Names declared nowhere: not in the document, not in the files it
+//! includes, and not by the game.
+
+use std::sync::Arc;
+
+use leek_semantic::{DeclarationKind, Reference, ReferenceKind, Resolution, SemanticModel};
+
+use super::{diagnostic, Linked, Project};
+use crate::builtins::catalog;
+use crate::documents::Document;
+use crate::index::IndexedFile;
+use crate::lsp::{Diagnostic, DocumentUri, Range};
+use crate::settings::LintLevel;
+
+pub(super) fn undefined_names(
+    document: &Document,
+    project: &Project<'_>,
+    linked: &Linked,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let (model, included) = (&linked.model, linked.included());
+    let version = project.settings.language_version;
+    for reference in model.references() {
+        if reference.resolution != Resolution::Unresolved || catalog().is_builtin(&reference.name, version) {
+            continue;
+        }
+        let (rule, mut message) = match reference.kind {
+            ReferenceKind::Call => ("undefined-function", format!("unknown function `{}`", reference.name)),
+            ReferenceKind::Read | ReferenceKind::Write => {
+                ("undefined-variable", format!("`{}` is not defined", reference.name))
+            }
+        };
+        if declared_later(model, reference) {
+            message = format!("`{}` is used before its declaration", reference.name);
+        } else if !linked.complete {
+            // Any other name may be declared by a file that cannot be
+            // found.
+            continue;
+        } else if let Some(suggestion) = suggestion(model, included, reference, version) {
+            message.push_str(&format!(", did you mean `{}`?", suggestion));
+        }
+        let range = Range {
+            start: document.position_at(reference.span.start),
+            end: document.position_at(reference.span.end),
+        };
+        diagnostics.extend(diagnostic(project.settings, rule, LintLevel::Error, range, message));
+    }
+}
+
+/// Whether a local variable of the scopes of `reference` has its name, but
+/// is declared after it.
+fn declared_later(model: &SemanticModel, reference: &Reference) -> bool {
+    let mut current = Some(reference.scope);
+    while let Some(id) = current {
+        let scope = model.scope(id);
+        let later = scope.declarations.iter().map(|&id| model.declaration(id)).any(|declaration| {
+            declaration.kind == DeclarationKind::Variable
+                && declaration.name == reference.name
+                && declaration.full_span.end > reference.span.start
+        });
+        if later {
+            return true;
+        }
+        if scope.isolated {
+            return false;
+        }
+        current = scope.parent;
+    }
+    false
+}
+
+/// The known name closest to the name of `reference`, if it is close
+/// enough to be a typo.
+fn suggestion<'a>(
+    model: &'a SemanticModel,
+    included: &'a [(DocumentUri, Arc<IndexedFile>)],
+    reference: &Reference,
+    version: u8,
+) -> Option<&'a str> {
+    let visible = model.visible_at(reference.span.start).into_iter().map(|id| model.declaration(id).name.as_str());
+    let exported = included.iter().flat_map(|(_, file)| file.model.exports().map(|(_, declaration)| declaration.name.as_str()));
+    let functions = catalog().functions(version).map(|function| function.name.as_str());
+    let constants = catalog().constants(version).map(|constant| constant.name.as_str());
+    let builtins = functions.chain(constants.filter(|_| reference.kind != ReferenceKind::Call));
+    // Any name is a single edit away from a one-letter name.
+    let length = reference.name.chars().count();
+    let threshold = (length / 3).max(1).min(length - 1);
+    visible
+        .chain(exported)
+        .chain(builtins)
+        .map(|name| (distance(&reference.name, name), name))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Levenshtein distance between `a` and `b`, a change of case counting as
+/// an edit.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(distance("getLife", "getLife"), 0);
+        assert_eq!(distance("getLfe", "getLife"), 1);
+        assert_eq!(distance("getlife", "getLife"), 1);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+}
@@ -0,0 +1,123 @@
+//! Declarations nothing uses. Globals and functions may be used by any file
+//! of the AI: the files included from an entry point of the project which
+//! reaches the document, or without entry points, any file linked to the
+//! document by includes.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use leek_semantic::{DeclarationKind, ReferenceKind, Resolution};
+
+use super::{diagnostic, resolve, Linked, Project};
+use crate::documents::Document;
+use crate::index::IndexedFile;
+use crate::lsp::{Diagnostic, DiagnosticTag, DocumentUri, Range};
+use crate::settings::LintLevel;
+use crate::uri;
+
+pub(super) fn unused_declarations(
+    document: &Document,
+    project: &Project<'_>,
+    linked: &Linked,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let model = &linked.model;
+    let ai = include_graph(document, project);
+    for (id, declaration) in model.declarations().iter().enumerate() {
+        // A leading underscore marks what is unused on purpose.
+        if declaration.name.starts_with('_') {
+            continue;
+        }
+        let (rule, message) = match declaration.kind {
+            DeclarationKind::Variable => ("unused-variable", format!("variable `{}` is never used", declaration.name)),
+            DeclarationKind::Parameter => ("unused-parameter", format!("parameter `{}` is never used", declaration.name)),
+            DeclarationKind::Global => ("unused-global", format!("global `{}` is never used", declaration.name)),
+            DeclarationKind::Function => ("unused-function", format!("function `{}` is never called", declaration.name)),
+            _ => continue,
+        };
+        // A function calling itself is not used for all that.
+        let callable = matches!(declaration.kind, DeclarationKind::Function | DeclarationKind::Method);
+        let used = model.references_to(id).any(|reference| {
+            reference.kind != ReferenceKind::Write
+                && !(callable && declaration.full_span.contains(&reference.span.start))
+        });
+        if used {
+            continue;
+        }
+        // The other files do not know which file declares the names they
+        // use.
+        let used_elsewhere = declaration.kind.is_global()
+            && ai.iter().any(|file| {
+                file.model.references().iter().any(|reference| {
+                    reference.name == declaration.name
+                        && reference.kind != ReferenceKind::Write
+                        && reference.resolution == Resolution::Unresolved
+                })
+            });
+        if used_elsewhere {
+            continue;
+        }
+        let range = Range {
+            start: document.position_at(declaration.span.start),
+            end: document.position_at(declaration.span.end),
+        };
+        if let Some(mut diagnostic) = diagnostic(project.settings, rule, LintLevel::Warning, range, message) {
+            diagnostic.tags = Some(vec![DiagnosticTag::Unnecessary]);
+            diagnostics.push(diagnostic);
+        }
+    }
+}
+
+/// Indexed files of the AIs the document is part of, the document
+/// excepted.
+fn include_graph(document: &Document, project: &Project<'_>) -> Vec<Arc<IndexedFile>> {
+    let mut includes: HashMap<DocumentUri, Vec<DocumentUri>> = HashMap::new();
+    let mut links: HashMap<DocumentUri, Vec<DocumentUri>> = HashMap::new();
+    for uri in project.index.uris() {
+        let file = match project.index.file(&uri) {
+            Some(file) => file,
+            None => continue,
+        };
+        for include in &file.includes {
+            if let Some(target) = resolve(project, &uri, &include.path) {
+                includes.entry(uri.clone()).or_default().push(target.clone());
+                links.entry(uri.clone()).or_default().push(target.clone());
+                links.entry(target).or_default().push(uri.clone());
+            }
+        }
+    }
+    let entry_points = project
+        .workspace
+        .root_of(&document.uri)
+        .and_then(|root| root.project.as_ref())
+        .map(|project| project.entry_points())
+        .unwrap_or_default();
+    let mut linked = HashSet::new();
+    for entry_point in entry_points.iter().filter_map(|path| uri::from_path(path)) {
+        let ai = reachable(&includes, entry_point);
+        if ai.contains(&document.uri) {
+            linked.extend(ai);
+        }
+    }
+    // Files no entry point reaches may be part of any AI including them.
+    if linked.is_empty() {
+        linked = reachable(&links, document.uri.clone());
+    }
+    linked.remove(&document.uri);
+    linked.iter().filter_map(|uri| project.index.file(uri)).collect()
+}
+
+/// Files reached from `from` following `edges`, `from` included.
+fn reachable(edges: &HashMap<DocumentUri, Vec<DocumentUri>>, from: DocumentUri) -> HashSet<DocumentUri> {
+    let mut visited = HashSet::new();
+    let mut pending = VecDeque::from([from]);
+    while let Some(uri) = pending.pop_front() {
+        if let Some(next) = edges.get(&uri) {
+            if !visited.contains(&uri) {
+                pending.extend(next.iter().cloned());
+            }
+        }
+        visited.insert(uri);
+    }
+    visited
+}
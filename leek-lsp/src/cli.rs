@@ -0,0 +1,174 @@
+//! Command-line interface of the `leek-lsp` binary.
+
+use std::path::{Path, PathBuf};
+
+use log::LevelFilter;
+use serde_json::Value;
+
+use crate::settings::{self, Settings, LATEST_VERSION};
+use crate::transport::Transport;
+
+pub const USAGE: &str = "\
+LeekScript language server
+
+Usage: leek-lsp [OPTIONS]
+
+Transport:
+    --stdio                  Talk to the client on stdin/stdout (default)
+    --port <PORT>            Connect to the client listening on this local
+                             TCP port (--socket <PORT> is accepted too)
+    --listen                 With --port, wait for the client to connect
+    --websocket <ADDRESS>    Wait for a WebSocket client on this address,
+                             e.g. 127.0.0.1:9258
+
+Options:
+    --log-file <PATH>        Write the logs to this file instead of stderr
+    --log-level <LEVEL>      off, error, warn, info (default), debug or trace
+    --config <PATH>          Settings file, in the JSON format of the
+                             `leekscript` editor settings, which override it
+    --print-capabilities     Print the server capabilities as JSON and exit
+    -h, --help               Print this help and exit
+    -V, --version            Print the version and exit
+";
+
+/// Options of the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub transport: Transport,
+    pub log_file: Option<PathBuf>,
+    pub log_level: LevelFilter,
+    pub config: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { transport: Transport::Stdio, log_file: None, log_level: LevelFilter::Info, config: None }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Serve(Options),
+    PrintCapabilities,
+    Help,
+    Version,
+}
+
+/// Parse the arguments of the binary, without the name of the program.
+/// Options taking a value accept both `--option value` and
+/// `--option=value`.
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut listen = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, mut value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = |what: &str| {
+            value
+                .take()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} expects {}", name, what))
+        };
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--print-capabilities" => return Ok(Command::PrintCapabilities),
+            "--stdio" => options.transport = Transport::Stdio,
+            "--port" | "--socket" => {
+                let port = value("a port number")?;
+                let port = port.parse().map_err(|_| format!("invalid port `{}`", port))?;
+                options.transport = Transport::Tcp { port, listen: false };
+            }
+            "--listen" => listen = true,
+            "--websocket" => {
+                let address = value("an address such as 127.0.0.1:9258")?;
+                let address = address.parse().map_err(|_| format!("invalid address `{}`", address))?;
+                options.transport = Transport::WebSocket(address);
+            }
+            "--log-file" => options.log_file = Some(PathBuf::from(value("a path")?)),
+            "--log-level" => {
+                let level = value("a level")?;
+                options.log_level = level.parse().map_err(|_| format!("invalid log level `{}`", level))?;
+            }
+            "--config" => options.config = Some(PathBuf::from(value("a path")?)),
+            _ => return Err(format!("unknown argument `{}`", name)),
+        }
+    }
+    match &mut options.transport {
+        Transport::Tcp { listen: tcp_listen, .. } => *tcp_listen = listen,
+        _ if listen => return Err("--listen only applies to --port".to_string()),
+        _ => {}
+    }
+    Ok(Command::Serve(options))
+}
+
+/// Read the settings file given with `--config`. Unlike the settings sent
+/// by the client, invalid settings are an error.
+pub fn read_config(path: &Path) -> Result<Value, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+    let value: Value = serde_json::from_str(&text).map_err(|err| format!("invalid {}: {}", path.display(), err))?;
+    let settings: Settings =
+        serde_json::from_value(value.clone()).map_err(|err| format!("invalid {}: {}", path.display(), err))?;
+    if !settings::is_valid_version(settings.language_version) {
+        return Err(format!("invalid {}: languageVersion must be 1 to {}", path.display(), LATEST_VERSION));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_arguments() {
+        assert_eq!(parse_args(&[]), Ok(Command::Serve(Options::default())));
+        assert_eq!(parse_args(&["--stdio", "--version"]), Ok(Command::Version));
+        assert_eq!(parse_args(&["--print-capabilities"]), Ok(Command::PrintCapabilities));
+
+        let options = Options {
+            transport: Transport::Tcp { port: 9257, listen: true },
+            log_file: Some(PathBuf::from("/tmp/leek.log")),
+            log_level: LevelFilter::Debug,
+            config: Some(PathBuf::from("leek.json")),
+        };
+        let args = ["--listen", "--port", "9257", "--log-file=/tmp/leek.log", "--log-level", "DEBUG", "--config", "leek.json"];
+        assert_eq!(parse_args(&args), Ok(Command::Serve(options)));
+        assert_eq!(
+            parse_args(&["--socket=9257"]),
+            Ok(Command::Serve(Options { transport: Transport::Tcp { port: 9257, listen: false }, ..Options::default() }))
+        );
+        assert_eq!(
+            parse_args(&["--websocket", "127.0.0.1:9258"]),
+            Ok(Command::Serve(Options { transport: Transport::WebSocket(([127, 0, 0, 1], 9258).into()), ..Options::default() }))
+        );
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(parse_args(&["--port"]), Err("--port expects a port number".to_string()));
+        assert_eq!(parse_args(&["--port", "http"]), Err("invalid port `http`".to_string()));
+        assert_eq!(parse_args(&["--log-level=loud"]), Err("invalid log level `loud`".to_string()));
+        assert_eq!(parse_args(&["--listen"]), Err("--listen only applies to --port".to_string()));
+        assert_eq!(parse_args(&["--verbose"]), Err("unknown argument `--verbose`".to_string()));
+    }
+
+    #[test]
+    fn config_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("leek.json");
+        std::fs::write(&path, r#"{ "languageVersion": 3 }"#).unwrap();
+        assert_eq!(read_config(&path).unwrap()["languageVersion"], 3);
+        std::fs::write(&path, r#"{ "languageVersion": "three" }"#).unwrap();
+        assert!(read_config(&path).unwrap_err().starts_with("invalid "));
+        std::fs::write(&path, r#"{ "languageVersion": 5 }"#).unwrap();
+        assert!(read_config(&path).unwrap_err().ends_with("languageVersion must be 1 to 4"));
+        assert!(read_config(&directory.path().join("missing.json")).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+use jsonrpc_core::{
+    to_string, to_value, Error, Id, MethodCall, Notification, Output, Params, Result as RPCResult, Value, Version,
+};
+use serde::Serialize;
+
+use crate::lsp::{Diagnostic, DocumentUri, PublishDiagnosticsParams};
+use crate::transport::Outgoing;
+
+type ResponseHandler = Box<dyn FnOnce(RPCResult<Value>) + Send>;
+
+/// Handle used by the server to send messages to the client.
+///
+/// Messages are serialized and queued; the transport writes them out and
+/// hands the responses to our own requests back through
+/// [`Client::handle_response`].
+#[derive(Clone)]
+pub struct Client {
+    sender: Sender<Outgoing>,
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Mutex<HashMap<Id, ResponseHandler>>>,
+}
+
+fn to_params<P: Serialize>(params: P) -> Params {
+    match to_value(params) {
+        Ok(Value::Object(map)) => Params::Map(map),
+        Ok(Value::Array(array)) => Params::Array(array),
+        _ => Params::None,
+    }
+}
+
+impl Client {
+    pub fn new(sender: Sender<Outgoing>) -> Self {
+        Client {
+            sender,
+            next_id: Arc::new(AtomicU64::new(0)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Queue an already serialized message.
+    pub fn send(&self, message: String) {
+        // The transport is gone when the receiver is dropped, there is
+        // nobody left to talk to.
+        let _ = self.sender.send(Outgoing::Message(message));
+    }
+
+    /// Tell the transport to stop once the messages queued so far are
+    /// written.
+    pub fn close(&self) {
+        let _ = self.sender.send(Outgoing::Close);
+    }
+
+    /// Send a notification to the client.
+    pub fn notify<P: Serialize>(&self, method: &str, params: P) {
+        let notification = Notification {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: to_params(params),
+        };
+        if let Ok(message) = to_string(&notification) {
+            self.send(message);
+        }
+    }
+
+    /// Send a request to the client. `handler` is called with the result
+    /// once the client answers.
+    ///
+    /// The handler runs on the transport thread: it must not block waiting
+    /// for other messages from the client.
+    pub fn request<P, F>(&self, method: &str, params: P, handler: F)
+    where
+        P: Serialize,
+        F: FnOnce(RPCResult<Value>) + Send + 'static,
+    {
+        let id = Id::Num(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let call = MethodCall {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: to_params(params),
+            id: id.clone(),
+        };
+        if let Ok(message) = to_string(&call) {
+            self.pending.lock().unwrap().insert(id, Box::new(handler));
+            self.send(message);
+        }
+    }
+
+    /// Route a response from the client to the handler of the matching
+    /// request. Returns `false` for responses to unknown requests.
+    pub fn handle_response(&self, output: Output) -> bool {
+        let id = match &output {
+            Output::Success(success) => success.id.clone(),
+            Output::Failure(failure) => failure.id.clone(),
+        };
+        let handler = self.pending.lock().unwrap().remove(&id);
+        match handler {
+            Some(handler) => {
+                handler(output.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fail every request still waiting for an answer, used when the
+    /// connection is closed.
+    pub fn cancel_pending(&self) {
+        let pending: Vec<_> = self.pending.lock().unwrap().drain().collect();
+        for (_, handler) in pending {
+            handler(Err(Error::internal_error()));
+        }
+    }
+
+    pub fn publish_diagnostics(&self, uri: DocumentUri, version: Option<i32>, diagnostics: Vec<Diagnostic>) {
+        self.notify("textDocument/publishDiagnostics", PublishDiagnosticsParams {
+            uri,
+            version,
+            diagnostics,
+        });
+    }
+}
@@ -0,0 +1,123 @@
+//! Delaying work until the user stops typing.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+#[derive(Default)]
+struct Pending {
+    /// When the work of each key is due.
+    deadlines: HashMap<String, Instant>,
+    stopped: bool,
+}
+
+/// Runs a callback for a key once it was not scheduled again for a while.
+///
+/// Callbacks run one at a time on a thread of the debouncer, which stops
+/// when the debouncer is dropped.
+pub struct Debouncer {
+    delay: Duration,
+    pending: Arc<(Mutex<Pending>, Condvar)>,
+}
+
+impl fmt::Debug for Debouncer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Debouncer").field("delay", &self.delay).finish_non_exhaustive()
+    }
+}
+
+impl Debouncer {
+    pub fn new<F>(delay: Duration, callback: F) -> Self
+    where
+        F: Fn(String) + Send + 'static,
+    {
+        let pending = Arc::new((Mutex::new(Pending::default()), Condvar::new()));
+        let shared = pending.clone();
+        let spawned = thread::Builder::new()
+            .name("debouncer".to_string())
+            .spawn(move || run(&shared, callback));
+        if let Err(err) = spawned {
+            warn!("cannot start the debouncer: {}", err);
+        }
+        Debouncer { delay, pending }
+    }
+
+    /// Run the callback for `key` after the delay, unless it is scheduled
+    /// again in the meantime.
+    pub fn schedule(&self, key: &str) {
+        let (pending, wakeup) = &*self.pending;
+        pending.lock().unwrap().deadlines.insert(key.to_string(), Instant::now() + self.delay);
+        wakeup.notify_one();
+    }
+
+    /// Forget the pending work of `key`.
+    pub fn cancel(&self, key: &str) {
+        self.pending.0.lock().unwrap().deadlines.remove(key);
+    }
+
+    /// See [`ServerState::clear_poison`](crate::server::ServerState::clear_poison).
+    pub fn clear_poison(&self) {
+        self.pending.0.clear_poison();
+    }
+}
+
+impl Drop for Debouncer {
+    fn drop(&mut self) {
+        let (pending, wakeup) = &*self.pending;
+        if let Ok(mut pending) = pending.lock() {
+            pending.stopped = true;
+        }
+        wakeup.notify_one();
+    }
+}
+
+fn run<F: Fn(String)>(shared: &(Mutex<Pending>, Condvar), callback: F) {
+    let (pending, wakeup) = shared;
+    let mut guard = pending.lock().unwrap();
+    while !guard.stopped {
+        let now = Instant::now();
+        let due: Vec<_> = guard.deadlines.iter().filter(|(_, deadline)| **deadline <= now).map(|(key, _)| key.clone()).collect();
+        if !due.is_empty() {
+            for key in &due {
+                guard.deadlines.remove(key);
+            }
+            drop(guard);
+            for key in due {
+                callback(key);
+            }
+            guard = pending.lock().unwrap();
+            continue;
+        }
+        guard = match guard.deadlines.values().min().copied() {
+            Some(deadline) => wakeup.wait_timeout(guard, deadline - now).unwrap().0,
+            None => wakeup.wait(guard).unwrap(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn run_once_things_calm_down() {
+        let (sender, receiver) = mpsc::channel();
+        let debouncer = Debouncer::new(Duration::from_millis(50), move |key| sender.send(key).unwrap());
+        for _ in 0..5 {
+            debouncer.schedule("a");
+            thread::sleep(Duration::from_millis(10));
+        }
+        debouncer.schedule("b");
+        debouncer.schedule("c");
+        debouncer.cancel("c");
+        let mut keys = vec![receiver.recv().unwrap(), receiver.recv().unwrap()];
+        keys.sort();
+        assert_eq!(keys, ["a", "b"]);
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use crate::documents::Document;
+use crate::lsp::{Diagnostic, DiagnosticSeverity, Either, Range};
+
+/// Value of the `source` field of every diagnostic we publish.
+pub const SOURCE: &str = "leekscript";
+
+/// Parse the document and report its syntax errors.
+pub fn syntax_diagnostics(document: &Document) -> Vec<Diagnostic> {
+    let source = document.text.to_string();
+    leek_parser::syntax_errors(&source)
+        .into_iter()
+        .map(|error| Diagnostic {
+            range: Range {
+                start: document.position_at(error.span.start),
+                end: document.position_at(error.span.end),
+            },
+            severity: Some(DiagnosticSeverity::Error),
+            code: Some(Either::Right(error.kind.code().to_string())),
+            code_description: None,
+            source: Some(SOURCE.to_string()),
+            message: error.message,
+            tags: None,
+            related_information: None,
+            data: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::{Position, TextDocumentItem};
+
+    #[test]
+    fn syntax_error_ranges() {
+        let document = Document::new(TextDocumentItem {
+            uri: "file:///ai.leek".to_string(),
+            language_id: "leekscript".to_string(),
+            version: 1,
+            text: "var a = 1;\nvar b = ;\n".to_string(),
+        });
+        let diagnostics = syntax_diagnostics(&document);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, Range {
+            start: Position { line: 1, character: 8 },
+            end: Position { line: 1, character: 9 },
+        });
+        assert_eq!(diagnostics[0].code, Some(Either::Right("E0001".to_string())));
+        assert_eq!(diagnostics[0].source.as_deref(), Some(SOURCE));
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use ropey::Rope;
+
+use crate::lsp::{DocumentUri, Position, TextDocumentContentChangeEvent, TextDocumentItem};
+
+/// A text document opened by the client.
+#[derive(Clone, Debug)]
+pub struct Document {
+    pub uri: DocumentUri,
+    pub language_id: String,
+    pub version: i32,
+    pub text: Rope,
+}
+
+impl Document {
+    pub fn new(item: TextDocumentItem) -> Self {
+        Document {
+            uri: item.uri,
+            language_id: item.language_id,
+            version: item.version,
+            text: Rope::from_str(&item.text),
+        }
+    }
+
+    /// A document read from the disk rather than opened by the client.
+    pub fn from_text(uri: DocumentUri, text: String) -> Self {
+        Document::new(TextDocumentItem { uri, language_id: "leekscript".to_string(), version: 0, text })
+    }
+
+    /// Convert an LSP position (line, UTF-16 code unit) into a char index
+    /// of the rope. Positions past the end of a line or of the document are
+    /// clamped, as required by the specification.
+    pub fn offset_at(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        if line >= self.text.len_lines() {
+            return self.text.len_chars();
+        }
+        let line_start = self.text.line_to_char(line);
+        let line_slice = self.text.line(line);
+        let mut line_len = line_slice.len_chars();
+        // A position never points inside the line terminator.
+        while line_len > 0 && matches!(line_slice.char(line_len - 1), '\n' | '\r') {
+            line_len -= 1;
+        }
+        let line_utf16 = line_slice.char_to_utf16_cu(line_len);
+        let character = (position.character as usize).min(line_utf16);
+        line_start + line_slice.utf16_cu_to_char(character)
+    }
+
+    /// Convert a byte offset in the document into an LSP position.
+    pub fn position_at(&self, offset: usize) -> Position {
+        let offset = self.text.byte_to_char(offset.min(self.text.len_bytes()));
+        let line = self.text.char_to_line(offset);
+        let line_start = self.text.line_to_char(line);
+        let character = self.text.char_to_utf16_cu(offset) - self.text.char_to_utf16_cu(line_start);
+        Position { line: line as u32, character: character as u32 }
+    }
+
+    /// Apply a single content change, either a range edit or a full
+    /// replacement of the document.
+    pub fn apply_change(&mut self, change: &TextDocumentContentChangeEvent) {
+        match change.range {
+            Some(range) => {
+                let start = self.offset_at(range.start);
+                let end = self.offset_at(range.end).max(start);
+                self.text.remove(start..end);
+                self.text.insert(start, &change.text);
+            }
+            None => self.text = Rope::from_str(&change.text),
+        }
+    }
+}
+
+/// Documents currently opened by the client, keyed by URI.
+///
+/// The store is cheap to clone and every clone shares the same documents.
+#[derive(Clone, Default, Debug)]
+pub struct DocumentStore {
+    /// Copied on write when a snapshot shares it.
+    documents: Arc<RwLock<Arc<HashMap<DocumentUri, Document>>>>,
+}
+
+impl DocumentStore {
+    pub fn open(&self, item: TextDocumentItem) {
+        let document = Document::new(item);
+        Arc::make_mut(&mut self.documents.write().unwrap()).insert(document.uri.clone(), document);
+    }
+
+    /// Apply `changes` in order to the document and record its new version.
+    /// Returns `false` if the document is not open.
+    pub fn change(&self, uri: &str, version: i32, changes: &[TextDocumentContentChangeEvent]) -> bool {
+        let mut documents = self.documents.write().unwrap();
+        match Arc::make_mut(&mut documents).get_mut(uri) {
+            Some(document) => {
+                for change in changes {
+                    document.apply_change(change);
+                }
+                document.version = version;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Called on `didSave`; when the client includes the saved content it
+    /// replaces ours, otherwise the document is left untouched.
+    pub fn save(&self, uri: &str, text: Option<&str>) -> bool {
+        let mut documents = self.documents.write().unwrap();
+        match Arc::make_mut(&mut documents).get_mut(uri) {
+            Some(document) => {
+                if let Some(text) = text {
+                    document.text = Rope::from_str(text);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn close(&self, uri: &str) -> Option<Document> {
+        Arc::make_mut(&mut self.documents.write().unwrap()).remove(uri)
+    }
+
+    pub fn get(&self, uri: &str) -> Option<Document> {
+        self.documents.read().unwrap().get(uri).cloned()
+    }
+
+    pub fn text(&self, uri: &str) -> Option<String> {
+        self.documents.read().unwrap().get(uri).map(|document| document.text.to_string())
+    }
+
+    pub fn version(&self, uri: &str) -> Option<i32> {
+        self.documents.read().unwrap().get(uri).map(|document| document.version)
+    }
+
+    /// A copy of the store, unaffected by the later changes of this one.
+    pub fn snapshot(&self) -> DocumentStore {
+        DocumentStore { documents: Arc::new(RwLock::new(self.documents.read().unwrap().clone())) }
+    }
+
+    /// See [`ServerState::clear_poison`](crate::server::ServerState::clear_poison).
+    pub fn clear_poison(&self) {
+        self.documents.clear_poison();
+    }
+
+    pub fn uris(&self) -> Vec<DocumentUri> {
+        self.documents.read().unwrap().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::Range;
+
+    fn item(text: &str) -> TextDocumentItem {
+        TextDocumentItem {
+            uri: "file:///ai.leek".to_string(),
+            language_id: "leekscript".to_string(),
+            version: 1,
+            text: text.to_string(),
+        }
+    }
+
+    fn edit(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: Position { line: start.0, character: start.1 },
+                end: Position { line: end.0, character: end.1 },
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn incremental_changes() {
+        let store = DocumentStore::default();
+        store.open(item("var a = 1;\nprint(a);\n"));
+        assert!(store.change("file:///ai.leek", 2, &[
+            edit((0, 4), (0, 5), "life"),
+            edit((1, 6), (1, 7), "life"),
+        ]));
+        assert_eq!(store.text("file:///ai.leek").unwrap(), "var life = 1;\nprint(life);\n");
+        assert_eq!(store.version("file:///ai.leek"), Some(2));
+    }
+
+    #[test]
+    fn full_change_and_close() {
+        let store = DocumentStore::default();
+        store.open(item("var a = 1;"));
+        let full = TextDocumentContentChangeEvent { range: None, range_length: None, text: "var b = 2;".to_string() };
+        store.change("file:///ai.leek", 5, &[full]);
+        assert_eq!(store.text("file:///ai.leek").unwrap(), "var b = 2;");
+        assert!(store.close("file:///ai.leek").is_some());
+        assert!(!store.change("file:///ai.leek", 6, &[]));
+    }
+
+    #[test]
+    fn utf16_positions() {
+        let store = DocumentStore::default();
+        // '😀' is two UTF-16 code units but a single char.
+        store.open(item("say(\"😀\");\nx\r\n"));
+        store.change("file:///ai.leek", 2, &[edit((0, 8), (0, 9), "!")]);
+        assert_eq!(store.text("file:///ai.leek").unwrap(), "say(\"😀\"!;\nx\r\n");
+        // Characters past the end of a line are clamped before the terminator.
+        store.change("file:///ai.leek", 3, &[edit((1, 10), (1, 10), "y")]);
+        assert_eq!(store.text("file:///ai.leek").unwrap(), "say(\"😀\"!;\nxy\r\n");
+    }
+}
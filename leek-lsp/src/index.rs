@@ -0,0 +1,336 @@
+//! Index of the symbols declared in the files of the workspace, open or
+//! not, used by the features looking at the whole project.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use leek_parser::{Pair, Rule};
+use leek_semantic::{DeclarationId, SemanticModel};
+
+use crate::documents::Document;
+use crate::lsp::{DocumentUri, Location, Range, SymbolInformation, SymbolKind};
+use crate::uri;
+
+/// Extension of LeekScript files.
+pub const EXTENSION: &str = "leek";
+
+/// A function or global variable declared in a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Range of the whole declaration.
+    pub range: Range,
+    /// Range of the name of the symbol.
+    pub selection_range: Range,
+    /// Name of the function the symbol is declared in.
+    pub container: Option<String>,
+}
+
+/// An `include` statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Include {
+    /// Path of the included file, as written.
+    pub path: String,
+    /// Range of the path, quotes included.
+    pub range: Range,
+}
+
+/// What the index knows about a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexedFile {
+    pub symbols: Vec<Symbol>,
+    pub includes: Vec<Include>,
+    /// Scopes and names of the file.
+    pub model: SemanticModel,
+    /// Range of the name of each declaration of the model, by id.
+    pub declaration_ranges: Vec<Range>,
+}
+
+impl IndexedFile {
+    pub fn declaration_range(&self, id: DeclarationId) -> Range {
+        self.declaration_ranges[id]
+    }
+}
+
+/// Index `document`. Files with syntax errors are empty until they are
+/// fixed.
+pub fn index_document(document: &Document) -> IndexedFile {
+    let source = document.text.to_string();
+    let mut file = IndexedFile::default();
+    if let Ok(pairs) = leek_parser::parse(&source) {
+        for pair in pairs {
+            collect(document, pair, None, &mut file);
+        }
+        file.model = leek_semantic::analyze(&source).unwrap_or_default();
+        file.declaration_ranges = file
+            .model
+            .declarations()
+            .iter()
+            .map(|declaration| Range {
+                start: document.position_at(declaration.span.start),
+                end: document.position_at(declaration.span.end),
+            })
+            .collect();
+    }
+    file
+}
+
+/// Symbols declared in `document`.
+pub fn document_symbols(document: &Document) -> Vec<Symbol> {
+    index_document(document).symbols
+}
+
+fn collect(document: &Document, pair: Pair<'_, Rule>, container: Option<&str>, file: &mut IndexedFile) {
+    let range = |pair: &Pair<'_, Rule>| Range {
+        start: document.position_at(pair.as_span().start()),
+        end: document.position_at(pair.as_span().end()),
+    };
+    match pair.as_rule() {
+        Rule::Defun => {
+            let name = pair.clone().into_inner().find(|pair| pair.as_rule() == Rule::Ident);
+            let name = match name {
+                Some(name) => name,
+                // Anonymous functions are not symbols, but what they
+                // declare belongs to the enclosing function.
+                None => {
+                    for inner in pair.into_inner() {
+                        collect(document, inner, container, file);
+                    }
+                    return;
+                }
+            };
+            file.symbols.push(Symbol {
+                name: name.as_str().to_string(),
+                kind: SymbolKind::Function,
+                range: range(&pair),
+                selection_range: range(&name),
+                container: container.map(str::to_string),
+            });
+            for inner in pair.into_inner() {
+                collect(document, inner, Some(name.as_str()), file);
+            }
+        }
+        // Variables declared in functions are locals.
+        Rule::Declr | Rule::Global if container.is_none() => {
+            if let Some(name) = pair.clone().into_inner().find(|pair| pair.as_rule() == Rule::Ident) {
+                file.symbols.push(Symbol {
+                    name: name.as_str().to_string(),
+                    kind: SymbolKind::Variable,
+                    range: range(&pair),
+                    selection_range: range(&name),
+                    container: None,
+                });
+            }
+            for inner in pair.into_inner() {
+                collect(document, inner, container, file);
+            }
+        }
+        Rule::Include => {
+            if let Some(path) = pair.into_inner().next() {
+                file.includes.push(Include { path: path.as_str().trim_matches('"').to_string(), range: range(&path) });
+            }
+        }
+        Rule::Class => {
+            if let Some(name) = pair.clone().into_inner().find(|pair| pair.as_rule() == Rule::Ident) {
+                file.symbols.push(Symbol {
+                    name: name.as_str().to_string(),
+                    kind: SymbolKind::Class,
+                    range: range(&pair),
+                    selection_range: range(&name),
+                    container: container.map(str::to_string),
+                });
+            }
+        }
+        _ => {
+            for inner in pair.into_inner() {
+                collect(document, inner, container, file);
+            }
+        }
+    }
+}
+
+/// Whether `name` matches the workspace symbol query `query`: the
+/// characters of the query appear in order in the name, ignoring case.
+pub fn matches(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|wanted| name.any(|c| c == wanted))
+}
+
+/// LeekScript files under `root`, skipping hidden directories.
+pub fn leek_files(root: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == EXTENSION))
+        .collect()
+}
+
+/// Indexed files, by file URI.
+#[derive(Default, Debug)]
+pub struct SymbolIndex {
+    /// Copied on write when a snapshot shares it.
+    files: RwLock<Arc<HashMap<DocumentUri, Arc<IndexedFile>>>>,
+}
+
+impl SymbolIndex {
+    /// Index the content of `document`.
+    pub fn update(&self, document: &Document) {
+        let file = Arc::new(index_document(document));
+        Arc::make_mut(&mut self.files.write().unwrap()).insert(document.uri.clone(), file);
+    }
+
+    /// Index the file at `path` from the disk. Returns `false` if the file
+    /// cannot be read.
+    pub fn update_from_disk(&self, path: &Path) -> bool {
+        let uri = match uri::from_path(path) {
+            Some(uri) => uri,
+            None => return false,
+        };
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                self.update(&Document::from_text(uri, text));
+                true
+            }
+            Err(_) => {
+                self.remove(&uri);
+                false
+            }
+        }
+    }
+
+    pub fn remove(&self, uri: &str) {
+        Arc::make_mut(&mut self.files.write().unwrap()).remove(uri);
+    }
+
+    /// Only keep the files whose URI satisfies `keep`.
+    pub fn retain(&self, keep: impl Fn(&str) -> bool) {
+        Arc::make_mut(&mut self.files.write().unwrap()).retain(|uri, _| keep(uri));
+    }
+
+    /// A copy of the index, unaffected by the later updates of this one.
+    pub fn snapshot(&self) -> SymbolIndex {
+        SymbolIndex { files: RwLock::new(self.files.read().unwrap().clone()) }
+    }
+
+    /// See [`ServerState::clear_poison`](crate::server::ServerState::clear_poison).
+    pub fn clear_poison(&self) {
+        self.files.clear_poison();
+    }
+
+    pub fn contains(&self, uri: &str) -> bool {
+        self.files.read().unwrap().contains_key(uri)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn file(&self, uri: &str) -> Option<Arc<IndexedFile>> {
+        self.files.read().unwrap().get(uri).cloned()
+    }
+
+    /// URIs of the indexed files.
+    pub fn uris(&self) -> Vec<DocumentUri> {
+        self.files.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Symbols of every file matching `query`, sorted by name.
+    pub fn search(&self, query: &str) -> Vec<SymbolInformation> {
+        let files = self.files.read().unwrap();
+        let mut results: Vec<_> = files
+            .iter()
+            .flat_map(|(uri, file)| file.symbols.iter().map(move |symbol| (uri, symbol)))
+            .filter(|(_, symbol)| matches(query, &symbol.name))
+            .map(|(uri, symbol)| SymbolInformation {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                deprecated: None,
+                location: Location { uri: uri.clone(), range: symbol.range },
+                container_name: symbol.container.clone(),
+            })
+            .collect();
+        results.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.location.cmp(&b.location)));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::Position;
+
+    #[test]
+    fn symbols_of_a_document() {
+        let document = Document::from_text(
+            "file:///ai.leek".to_string(),
+            "var life = 1;\nfunction attack(target) {\n    var damage = 2;\n    function helper() { return 1; }\n}\n".to_string(),
+        );
+        let symbols = document_symbols(&document);
+        let names: Vec<_> = symbols.iter().map(|symbol| (symbol.name.as_str(), symbol.container.as_deref())).collect();
+        assert_eq!(names, vec![("life", None), ("attack", None), ("helper", Some("attack"))]);
+        assert_eq!(symbols[1].selection_range.start, Position { line: 1, character: 9 });
+        assert_eq!(symbols[1].range.end, Position { line: 4, character: 1 });
+
+        let broken = Document::from_text("file:///broken.leek".to_string(), "var a = ;".to_string());
+        assert!(document_symbols(&broken).is_empty());
+    }
+
+    #[test]
+    fn includes_and_symbols() {
+        let document = Document::from_text(
+            "file:///ai.leek".to_string(),
+            "include(\"lib/util.leek\");\nvar a = heal(move(1), attack);\nclass Leek { life = 1; }\n".to_string(),
+        );
+        let file = index_document(&document);
+        assert_eq!(file.includes, vec![Include {
+            path: "lib/util.leek".to_string(),
+            range: Range { start: Position { line: 0, character: 8 }, end: Position { line: 0, character: 23 } },
+        }]);
+        let symbols: Vec<_> = file.symbols.iter().map(|symbol| (symbol.name.as_str(), symbol.kind)).collect();
+        assert_eq!(symbols, [("a", SymbolKind::Variable), ("Leek", SymbolKind::Class)]);
+    }
+
+    #[test]
+    fn search_symbols() {
+        assert!(matches("atk", "attack"));
+        assert!(matches("", "attack"));
+        assert!(!matches("kta", "attack"));
+
+        let index = SymbolIndex::default();
+        index.update(&Document::from_text("file:///a/ai.leek".to_string(), "function attack() { return 1; }".to_string()));
+        index.update(&Document::from_text("file:///b/ai.leek".to_string(), "var atk = 1;".to_string()));
+        let names: Vec<_> = index.search("ATK").into_iter().map(|symbol| symbol.name).collect();
+        assert_eq!(names, vec!["atk", "attack"]);
+
+        index.retain(|uri| !uri::contains("file:///a", uri));
+        assert!(!index.contains("file:///a/ai.leek"));
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn find_leek_files() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("lib")).unwrap();
+        std::fs::create_dir_all(root.path().join(".git")).unwrap();
+        std::fs::write(root.path().join("main.leek"), "").unwrap();
+        std::fs::write(root.path().join("lib/util.leek"), "").unwrap();
+        std::fs::write(root.path().join(".git/old.leek"), "").unwrap();
+        std::fs::write(root.path().join("notes.txt"), "").unwrap();
+        let mut files = leek_files(root.path());
+        files.sort();
+        assert_eq!(files, vec![root.path().join("lib/util.leek"), root.path().join("main.leek")]);
+    }
+}
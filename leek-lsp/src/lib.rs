@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate jsonrpc_derive;
+
+pub mod analysis;
+pub mod builtins;
+pub mod checks;
+pub mod cli;
+pub mod client;
+pub mod debounce;
+pub mod diagnostics;
+pub mod documents;
+pub mod index;
+pub mod lifecycle;
+pub mod logging;
+pub mod lsp;
+pub mod negotiation;
+pub mod panics;
+pub mod progress;
+pub mod project;
+pub mod scheduler;
+pub mod server;
+pub mod settings;
+pub mod transport;
+pub mod uri;
+pub mod watcher;
+pub mod workspace;
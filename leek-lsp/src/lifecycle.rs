@@ -0,0 +1,214 @@
+//! Server lifecycle, enforced as a middleware in front of every handler.
+//!
+//! ```text
+//! Uninitialized --initialize--> Initializing --initialized--> Initialized
+//!                                     \                            /
+//!                                      `--------shutdown---------'
+//!                                                  |
+//!                                              ShutDown --exit--> (exit code 0)
+//! ```
+//!
+//! `exit` received in any other state terminates the server with code 1.
+
+use std::sync::{Arc, Mutex};
+
+use jsonrpc_core::futures::future::{self, Either, Future};
+use jsonrpc_core::middleware::{NoopCallFuture, NoopFuture};
+use jsonrpc_core::{Call, Metadata, Middleware, Output};
+
+use crate::lsp::errors;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    /// Waiting for the `initialize` request.
+    Uninitialized,
+    /// `initialize` was received, waiting for the `initialized` notification.
+    Initializing,
+    Initialized,
+    /// `shutdown` was received, only `exit` is accepted.
+    ShutDown,
+}
+
+/// Shared lifecycle state; clones observe the same state.
+#[derive(Clone, Debug)]
+pub struct Lifecycle {
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Lifecycle { state: Arc::new(Mutex::new(State::Uninitialized)) }
+    }
+}
+
+/// What to do with an incoming message.
+enum Admission {
+    Accept,
+    /// Reply with an error; dropped silently for notifications.
+    Reject(jsonrpc_core::Error),
+}
+
+impl Lifecycle {
+    pub fn state(&self) -> State {
+        *self.state.lock().unwrap()
+    }
+
+    /// Exit code of the process when the `exit` notification arrives.
+    pub fn exit_code(&self) -> i32 {
+        match self.state() {
+            State::ShutDown => 0,
+            _ => 1,
+        }
+    }
+
+    /// Decide whether a message can be handled in the current state and
+    /// perform the transition it triggers.
+    fn admit(&self, method: &str, is_request: bool) -> Admission {
+        let mut state = self.state.lock().unwrap();
+        match (*state, method) {
+            (_, "exit") => Admission::Accept,
+            (State::Uninitialized, "initialize") => {
+                *state = State::Initializing;
+                Admission::Accept
+            }
+            (State::Uninitialized, _) => Admission::Reject(errors::server_not_initialized()),
+            (State::ShutDown, _) => Admission::Reject(errors::invalid_request("Server is shutting down")),
+            (_, "initialize") => Admission::Reject(errors::invalid_request("Server is already initialized")),
+            (State::Initializing, "initialized") if !is_request => {
+                *state = State::Initialized;
+                Admission::Accept
+            }
+            (_, "shutdown") => {
+                *state = State::ShutDown;
+                Admission::Accept
+            }
+            _ => Admission::Accept,
+        }
+    }
+
+    /// Go back to the uninitialized state when `initialize` failed.
+    fn initialize_failed(&self) {
+        let mut state = self.state.lock().unwrap();
+        if *state == State::Initializing {
+            *state = State::Uninitialized;
+        }
+    }
+}
+
+impl<M: Metadata> Middleware<M> for Lifecycle {
+    type Future = NoopFuture;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+    {
+        let (method, is_request) = match &call {
+            Call::MethodCall(call) => (call.method.clone(), true),
+            Call::Notification(notification) => (notification.method.clone(), false),
+            Call::Invalid { .. } => return Either::B(next(call, meta)),
+        };
+        match self.admit(&method, is_request) {
+            Admission::Accept if method == "initialize" => {
+                let lifecycle = self.clone();
+                Either::A(Box::new(next(call, meta).map(move |output| {
+                    if let Some(Output::Failure(_)) = output {
+                        lifecycle.initialize_failed();
+                    }
+                    output
+                })))
+            }
+            Admission::Accept => Either::B(next(call, meta)),
+            Admission::Reject(error) => match call {
+                Call::MethodCall(call) => {
+                    Either::A(Box::new(future::ok(Some(Output::from(Err(error), call.id, call.jsonrpc)))))
+                }
+                _ => Either::A(Box::new(future::ok(None))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{MetaIoHandler, Params, Value};
+
+    fn handler(lifecycle: &Lifecycle) -> MetaIoHandler<(), Lifecycle> {
+        let mut io = MetaIoHandler::with_middleware(lifecycle.clone());
+        io.add_method("initialize", |params: Params| match params {
+            Params::None => Err(jsonrpc_core::Error::invalid_params("missing params")),
+            _ => Ok(Value::Null),
+        });
+        io.add_method("shutdown", |_| Ok(Value::Null));
+        io.add_method("textDocument/hover", |_| Ok(Value::Null));
+        io.add_notification("initialized", |_| {});
+        io.add_notification("exit", |_| {});
+        io
+    }
+
+    fn call(io: &MetaIoHandler<(), Lifecycle>, method: &str, params: &str) -> Value {
+        let request = format!("{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"{}\",\"params\":{}}}", method, params);
+        serde_json::from_str(&io.handle_request_sync(&request, ()).unwrap()).unwrap()
+    }
+
+    fn notify(io: &MetaIoHandler<(), Lifecycle>, method: &str) {
+        let notification = format!("{{\"jsonrpc\":\"2.0\",\"method\":\"{}\",\"params\":{{}}}}", method);
+        assert_eq!(io.handle_request_sync(&notification, ()), None);
+    }
+
+    #[test]
+    fn requests_before_initialize() {
+        let lifecycle = Lifecycle::default();
+        let io = handler(&lifecycle);
+        let response = call(&io, "textDocument/hover", "{}");
+        assert_eq!(response["error"]["code"], errors::SERVER_NOT_INITIALIZED);
+        notify(&io, "initialized");
+        assert_eq!(lifecycle.state(), State::Uninitialized);
+        let response = call(&io, "shutdown", "null");
+        assert_eq!(response["error"]["code"], errors::SERVER_NOT_INITIALIZED);
+    }
+
+    #[test]
+    fn failed_initialize_can_be_retried() {
+        let lifecycle = Lifecycle::default();
+        let io = handler(&lifecycle);
+        let request = "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\"}";
+        let response: Value = serde_json::from_str(&io.handle_request_sync(request, ()).unwrap()).unwrap();
+        assert!(response.get("error").is_some());
+        assert_eq!(lifecycle.state(), State::Uninitialized);
+        assert_eq!(call(&io, "initialize", "{}")["result"], Value::Null);
+        assert_eq!(lifecycle.state(), State::Initializing);
+    }
+
+    #[test]
+    fn full_lifecycle() {
+        let lifecycle = Lifecycle::default();
+        let io = handler(&lifecycle);
+        assert_eq!(call(&io, "initialize", "{}")["result"], Value::Null);
+        assert_eq!(lifecycle.state(), State::Initializing);
+        assert_eq!(call(&io, "initialize", "{}")["error"]["code"], -32600);
+        notify(&io, "initialized");
+        assert_eq!(lifecycle.state(), State::Initialized);
+        assert_eq!(call(&io, "textDocument/hover", "{}")["result"], Value::Null);
+        assert_eq!(lifecycle.exit_code(), 1);
+
+        assert_eq!(call(&io, "shutdown", "null")["result"], Value::Null);
+        assert_eq!(lifecycle.state(), State::ShutDown);
+        assert_eq!(call(&io, "textDocument/hover", "{}")["error"]["code"], -32600);
+        assert_eq!(call(&io, "shutdown", "null")["error"]["code"], -32600);
+        notify(&io, "exit");
+        assert_eq!(lifecycle.exit_code(), 0);
+    }
+
+    #[test]
+    fn exit_without_shutdown() {
+        let lifecycle = Lifecycle::default();
+        assert_eq!(lifecycle.exit_code(), 1);
+        let io = handler(&lifecycle);
+        call(&io, "initialize", "{}");
+        notify(&io, "initialized");
+        assert_eq!(lifecycle.exit_code(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+//! Logging for the server.
+//!
+//! stdout carries the protocol, so log records are written to stderr or to a
+//! log file, and forwarded to the client with `window/logMessage` once it is
+//! attached. Protocol traces requested by the client through the `trace`
+//! setting are sent as `$/logTrace` notifications.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, RwLock};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::client::Client;
+use crate::lsp::{LogMessageParams, LogTraceParams, MessageType, TraceValue};
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+pub struct Logger {
+    level: LevelFilter,
+    output: Mutex<Box<dyn Write + Send>>,
+    client: RwLock<Option<Client>>,
+    trace: RwLock<TraceValue>,
+}
+
+impl Logger {
+    pub fn new(level: LevelFilter, output: Box<dyn Write + Send>) -> Self {
+        Logger {
+            level,
+            output: Mutex::new(output),
+            client: RwLock::new(None),
+            trace: RwLock::new(TraceValue::Off),
+        }
+    }
+
+    /// Forward log records and traces to `client`.
+    pub fn attach_client(&self, client: Client) {
+        *self.client.write().unwrap() = Some(client);
+    }
+
+    pub fn set_trace(&self, trace: TraceValue) {
+        *self.trace.write().unwrap() = trace;
+    }
+
+    /// Send a `$/logTrace` notification if the client asked for traces.
+    /// `verbose` is only evaluated in verbose mode.
+    pub fn log_trace<F: FnOnce() -> String>(&self, message: &str, verbose: F) {
+        let verbose = match *self.trace.read().unwrap() {
+            TraceValue::Off => return,
+            TraceValue::Messages => None,
+            TraceValue::Verbose => Some(verbose()),
+        };
+        if let Some(client) = &*self.client.read().unwrap() {
+            client.notify("$/logTrace", LogTraceParams { message: message.to_string(), verbose });
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let _ = writeln!(self.output.lock().unwrap(), "[{} {}] {}", record.level(), record.target(), record.args());
+
+        // Only our own records are worth showing in the editor.
+        if !record.target().starts_with("leek") {
+            return;
+        }
+        if let Some(client) = &*self.client.read().unwrap() {
+            let typ = match record.level() {
+                Level::Error => MessageType::Error,
+                Level::Warn => MessageType::Warning,
+                Level::Info => MessageType::Info,
+                Level::Debug | Level::Trace => MessageType::Log,
+            };
+            client.notify("window/logMessage", LogMessageParams { typ, message: record.args().to_string() });
+        }
+    }
+
+    fn flush(&self) {
+        let _ = self.output.lock().unwrap().flush();
+    }
+}
+
+/// Install the global logger, writing to `file` if given and to stderr
+/// otherwise.
+pub fn init(level: LevelFilter, file: Option<&Path>) -> io::Result<()> {
+    let output: Box<dyn Write + Send> = match file {
+        Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+        None => Box::new(io::stderr()),
+    };
+    let logger = LOGGER.get_or_init(|| Logger::new(level, output));
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+    Ok(())
+}
+
+pub fn attach_client(client: Client) {
+    if let Some(logger) = LOGGER.get() {
+        logger.attach_client(client);
+    }
+}
+
+pub fn set_trace(trace: TraceValue) {
+    if let Some(logger) = LOGGER.get() {
+        logger.set_trace(trace);
+    }
+}
+
+pub fn log_trace<F: FnOnce() -> String>(message: &str, verbose: F) {
+    if let Some(logger) = LOGGER.get() {
+        logger.log_trace(message, verbose);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport;
+
+    #[test]
+    fn forward_to_client() {
+        let (sender, outgoing) = transport::outgoing();
+        let logger = Logger::new(LevelFilter::Info, Box::new(io::sink()));
+        logger.attach_client(Client::new(sender));
+
+        logger.log(&Record::builder().level(Level::Debug).target("leek_lsp").args(format_args!("hidden")).build());
+        logger.log(&Record::builder().level(Level::Info).target("jsonrpc_core").args(format_args!("hidden")).build());
+        logger.log(&Record::builder().level(Level::Warn).target("leek_lsp").args(format_args!("careful")).build());
+        let message = outgoing.try_recv().unwrap().into_message().unwrap();
+        let message: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(message["method"], "window/logMessage");
+        assert_eq!(message["params"], serde_json::json!({ "type": 2, "message": "careful" }));
+        assert!(outgoing.try_recv().is_err());
+    }
+
+    #[test]
+    fn trace_levels() {
+        let (sender, outgoing) = transport::outgoing();
+        let logger = Logger::new(LevelFilter::Info, Box::new(io::sink()));
+        logger.attach_client(Client::new(sender));
+
+        logger.log_trace("off", || unreachable!());
+        assert!(outgoing.try_recv().is_err());
+
+        logger.set_trace(TraceValue::Messages);
+        logger.log_trace("messages", || unreachable!());
+        let message = outgoing.try_recv().unwrap().into_message().unwrap();
+        let message: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(message["params"], serde_json::json!({ "message": "messages" }));
+
+        logger.set_trace(TraceValue::Verbose);
+        logger.log_trace("verbose", || "details".to_string());
+        let message = outgoing.try_recv().unwrap().into_message().unwrap();
+        let message: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(message["method"], "$/logTrace");
+        assert_eq!(message["params"], serde_json::json!({ "message": "verbose", "verbose": "details" }));
+    }
+}
@@ -0,0 +1,46 @@
+use jsonrpc_core::{Error, ErrorCode};
+
+/// Error codes defined by the LSP specification on top of JSON-RPC ones.
+pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+pub const CONTENT_MODIFIED: i64 = -32801;
+pub const REQUEST_CANCELLED: i64 = -32800;
+
+pub fn server_not_initialized() -> Error {
+    Error {
+        code: ErrorCode::ServerError(SERVER_NOT_INITIALIZED),
+        message: "Server not initialized".to_string(),
+        data: None,
+    }
+}
+
+pub fn request_cancelled() -> Error {
+    Error {
+        code: ErrorCode::ServerError(REQUEST_CANCELLED),
+        message: "Request cancelled".to_string(),
+        data: None,
+    }
+}
+
+pub fn content_modified() -> Error {
+    Error {
+        code: ErrorCode::ServerError(CONTENT_MODIFIED),
+        message: "Content modified".to_string(),
+        data: None,
+    }
+}
+
+pub fn internal_error(message: &str) -> Error {
+    Error {
+        code: ErrorCode::InternalError,
+        message: format!("Internal error: {}", message),
+        data: None,
+    }
+}
+
+pub fn invalid_request(message: &str) -> Error {
+    Error {
+        code: ErrorCode::InvalidRequest,
+        message: message.to_string(),
+        data: None,
+    }
+}
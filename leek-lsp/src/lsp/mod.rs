@@ -1,12 +1,47 @@
+pub mod errors;
 mod structs;
 
-use jsonrpc_core::Result as RPCResult;
+use jsonrpc_core::{Params, Result as RPCResult};
 pub use structs::*;
 
-#[rpc]
+#[rpc(server)]
 pub trait LSP {
-    #[name="initialize"]
-    fn initialize(params: InitalizeParams<(), ()>) -> RPCResult<InitializeResult>;
-    fn shutdown() -> RPCResult<()>;
-    fn exit() -> RPCResult<()>;
-}
\ No newline at end of file
+    #[rpc(name = "initialize", params = "raw")]
+    fn initialize(&self, params: Params) -> RPCResult<InitializeResult>;
+
+    #[rpc(name = "initialized", params = "raw")]
+    fn initialized(&self, params: Params);
+
+    #[rpc(name = "shutdown")]
+    fn shutdown(&self) -> RPCResult<()>;
+
+    #[rpc(name = "exit")]
+    fn exit(&self);
+
+    #[rpc(name = "$/setTrace", params = "raw")]
+    fn set_trace(&self, params: Params);
+
+    #[rpc(name = "workspace/didChangeConfiguration", params = "raw")]
+    fn did_change_configuration(&self, params: Params);
+
+    #[rpc(name = "workspace/didChangeWorkspaceFolders", params = "raw")]
+    fn did_change_workspace_folders(&self, params: Params);
+
+    #[rpc(name = "workspace/didChangeWatchedFiles", params = "raw")]
+    fn did_change_watched_files(&self, params: Params);
+
+    #[rpc(name = "workspace/symbol", params = "raw")]
+    fn workspace_symbol(&self, params: Params) -> RPCResult<Vec<SymbolInformation>>;
+
+    #[rpc(name = "textDocument/didOpen", params = "raw")]
+    fn did_open(&self, params: Params);
+
+    #[rpc(name = "textDocument/didChange", params = "raw")]
+    fn did_change(&self, params: Params);
+
+    #[rpc(name = "textDocument/didSave", params = "raw")]
+    fn did_save(&self, params: Params);
+
+    #[rpc(name = "textDocument/didClose", params = "raw")]
+    fn did_close(&self, params: Params);
+}
@@ -0,0 +1,413 @@
+//! Basic structures shared by the whole protocol.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_with::skip_serializing_none;
+
+use super::{Either, WorkspaceFolder};
+
+pub type DocumentUri = String;
+pub type Uri = String;
+pub type LSPAny = Value;
+pub type LSPObject = serde_json::Map<String, Value>;
+pub type ProgressToken = Either<i32, String>;
+pub type ChangeAnnotationIdentifier = String;
+pub type Pattern = String;
+pub type GlobPattern = Either<Pattern, RelativePattern>;
+pub type DocumentSelector = Vec<DocumentFilter>;
+
+/// Well known values are listed in [`position_encoding_kind`].
+pub type PositionEncodingKind = String;
+
+pub mod position_encoding_kind {
+    pub const UTF8: &str = "utf-8";
+    pub const UTF16: &str = "utf-16";
+    pub const UTF32: &str = "utf-32";
+}
+
+/// Identifier of a request, used by `$/cancelRequest`.
+pub type RequestId = Either<i32, String>;
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CancelParams {
+    pub id: RequestId,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ProgressParams<T = Value> {
+    pub token: ProgressToken,
+    pub value: T,
+}
+
+#[derive(Copy, Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Copy, Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Location {
+    pub uri: DocumentUri,
+    pub range: Range,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct LocationLink {
+    pub origin_selection_range: Option<Range>,
+    pub target_uri: DocumentUri,
+    pub target_range: Range,
+    pub target_selection_range: Range,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: Option<DiagnosticSeverity>,
+    pub code: Option<Either<i32, String>>,
+    pub code_description: Option<CodeDescription>,
+    pub source: Option<String>,
+    pub message: String,
+    pub tags: Option<Vec<DiagnosticTag>>,
+    pub related_information: Option<Vec<DiagnosticRelatedInformation>>,
+    pub data: Option<Value>,
+}
+
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+    Hint = 4,
+}
+
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum DiagnosticTag {
+    /// Unused or unnecessary code, rendered faded out by clients.
+    Unnecessary = 1,
+    Deprecated = 2,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DiagnosticRelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CodeDescription {
+    pub href: Uri,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct Command {
+    pub title: String,
+    pub command: String,
+    pub arguments: Option<Vec<Value>>,
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ChangeAnnotation {
+    pub label: String,
+    pub needs_confirmation: Option<bool>,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct AnnotatedTextEdit {
+    #[serde(flatten)]
+    pub text_edit: TextEdit,
+    pub annotation_id: ChangeAnnotationIdentifier,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentEdit {
+    pub text_document: OptionalVersionedTextDocumentIdentifier,
+    pub edits: Vec<Either<AnnotatedTextEdit, TextEdit>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CreateFileOptions {
+    pub overwrite: Option<bool>,
+    pub ignore_if_exists: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CreateFile {
+    pub uri: DocumentUri,
+    pub options: Option<CreateFileOptions>,
+    pub annotation_id: Option<ChangeAnnotationIdentifier>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RenameFileOptions {
+    pub overwrite: Option<bool>,
+    pub ignore_if_exists: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RenameFile {
+    pub old_uri: DocumentUri,
+    pub new_uri: DocumentUri,
+    pub options: Option<RenameFileOptions>,
+    pub annotation_id: Option<ChangeAnnotationIdentifier>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DeleteFileOptions {
+    pub recursive: Option<bool>,
+    pub ignore_if_not_exists: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DeleteFile {
+    pub uri: DocumentUri,
+    pub options: Option<DeleteFileOptions>,
+    pub annotation_id: Option<ChangeAnnotationIdentifier>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag="kind", rename_all="lowercase")]
+pub enum ResourceOperation {
+    Create(CreateFile),
+    Rename(RenameFile),
+    Delete(DeleteFile),
+}
+
+/// An entry of `WorkspaceEdit.documentChanges`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum DocumentChange {
+    Edit(TextDocumentEdit),
+    Operation(ResourceOperation),
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceEdit {
+    pub changes: Option<HashMap<DocumentUri, Vec<TextEdit>>>,
+    pub document_changes: Option<Vec<DocumentChange>>,
+    pub change_annotations: Option<HashMap<ChangeAnnotationIdentifier, ChangeAnnotation>>,
+}
+
+#[derive(Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct TextDocumentIdentifier {
+    pub uri: DocumentUri,
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentItem {
+    pub uri: DocumentUri,
+    pub language_id: String,
+    pub version: i32,
+    pub text: String,
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct VersionedTextDocumentIdentifier {
+    pub uri: DocumentUri,
+    pub version: i32,
+}
+
+/// `version` is `null` for documents not opened by the client.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct OptionalVersionedTextDocumentIdentifier {
+    pub uri: DocumentUri,
+    pub version: Option<i32>,
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentPositionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentFilter {
+    pub language: Option<String>,
+    pub scheme: Option<String>,
+    pub pattern: Option<Pattern>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RelativePattern {
+    pub base_uri: Either<WorkspaceFolder, Uri>,
+    pub pattern: Pattern,
+}
+
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum MarkupKind {
+    PlainText,
+    Markdown,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MarkupContent {
+    pub kind: MarkupKind,
+    pub value: String,
+}
+
+/// Deprecated in favor of [`MarkupContent`], still used by hovers.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum MarkedString {
+    String(String),
+    LanguageString { language: String, value: String },
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkDoneProgressBegin {
+    pub title: String,
+    pub cancellable: Option<bool>,
+    pub message: Option<String>,
+    pub percentage: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkDoneProgressReport {
+    pub cancellable: Option<bool>,
+    pub message: Option<String>,
+    pub percentage: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkDoneProgressEnd {
+    pub message: Option<String>,
+}
+
+/// Value of a `$/progress` notification reporting work done progress.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag="kind", rename_all="lowercase")]
+pub enum WorkDoneProgress {
+    Begin(WorkDoneProgressBegin),
+    Report(WorkDoneProgressReport),
+    End(WorkDoneProgressEnd),
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkDoneProgressParams {
+    pub work_done_token: Option<ProgressToken>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct PartialResultParams {
+    pub partial_result_token: Option<ProgressToken>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkDoneProgressOptions {
+    pub work_done_progress: Option<bool>,
+}
+
+#[derive(Copy, Clone, Default, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum TraceValue {
+    #[default]
+    Off,
+    Messages,
+    Verbose,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct StaticRegistrationOptions {
+    pub id: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentRegistrationOptions {
+    pub document_selector: Option<DocumentSelector>,
+}
+
+/// Registration options shared by most language features: the documents
+/// they apply to, an optional registration id and progress support.
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RegistrationOptions {
+    pub document_selector: Option<DocumentSelector>,
+    pub id: Option<String>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RegularExpressionsClientCapabilities {
+    pub engine: String,
+    pub version: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct MarkdownClientCapabilities {
+    pub parser: String,
+    pub version: Option<String>,
+    pub allowed_tags: Option<Vec<String>>,
+}
@@ -0,0 +1,645 @@
+//! Capabilities announced by the client in `initialize` and by the server
+//! in its response.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_with::skip_serializing_none;
+
+use super::{
+    lenient, lenient_one, lenient_option, CodeActionKind, CompletionItemKind, CompletionItemTag, DiagnosticTag,
+    Either, FileOperationRegistrationOptions, FoldingRangeKind, MarkdownClientCapabilities,
+    MarkupKind, PositionEncodingKind, RegistrationOptions, RegularExpressionsClientCapabilities,
+    SemanticTokensLegend, StaticRegistrationOptions, SymbolKind, SymbolTag, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TokenFormat, WorkDoneProgressOptions,
+};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum InsertTextMode {
+    AsIs = 1,
+    AdjustIndentation = 2,
+}
+
+/// A set of enumeration values supported by the client.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase", bound(deserialize="T: DeserializeOwned"))]
+pub struct ValueSet<T> {
+    #[serde(default = "Vec::new", deserialize_with="lenient")]
+    pub value_set: Vec<T>,
+}
+
+impl<T> Default for ValueSet<T> {
+    fn default() -> Self {
+        ValueSet { value_set: Vec::new() }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Properties {
+    pub properties: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DynamicRegistrationCapabilities {
+    pub dynamic_registration: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ClientCapabilities {
+    pub workspace: Option<WorkspaceClientCapabilities>,
+    pub text_document: Option<TextDocumentClientCapabilities>,
+    pub notebook_document: Option<Value>,
+    pub window: Option<WindowClientCapabilities>,
+    pub general: Option<GeneralClientCapabilities>,
+    pub experimental: Option<Value>,
+}
+
+// Workspace.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceClientCapabilities {
+    pub apply_edit: Option<bool>,
+    pub workspace_edit: Option<WorkspaceEditClientCapabilities>,
+    pub did_change_configuration: Option<DynamicRegistrationCapabilities>,
+    pub did_change_watched_files: Option<DidChangeWatchedFilesClientCapabilities>,
+    pub symbol: Option<WorkspaceSymbolClientCapabilities>,
+    pub execute_command: Option<DynamicRegistrationCapabilities>,
+    pub workspace_folders: Option<bool>,
+    pub configuration: Option<bool>,
+    pub semantic_tokens: Option<RefreshClientCapabilities>,
+    pub code_lens: Option<RefreshClientCapabilities>,
+    pub file_operations: Option<FileOperationClientCapabilities>,
+    pub inline_value: Option<RefreshClientCapabilities>,
+    pub inlay_hint: Option<RefreshClientCapabilities>,
+    pub diagnostics: Option<RefreshClientCapabilities>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub enum ResourceOperationKind {
+    Create,
+    Rename,
+    Delete,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub enum FailureHandlingKind {
+    Abort,
+    Transactional,
+    TextOnlyTransactional,
+    Undo,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ChangeAnnotationSupport {
+    pub groups_on_label: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceEditClientCapabilities {
+    pub document_changes: Option<bool>,
+    #[serde(default, deserialize_with="lenient_option")]
+    pub resource_operations: Option<Vec<ResourceOperationKind>>,
+    #[serde(default, deserialize_with="lenient_one")]
+    pub failure_handling: Option<FailureHandlingKind>,
+    pub normalizes_line_endings: Option<bool>,
+    pub change_annotation_support: Option<ChangeAnnotationSupport>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DidChangeWatchedFilesClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub relative_pattern_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceSymbolClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub symbol_kind: Option<ValueSet<SymbolKind>>,
+    pub tag_support: Option<ValueSet<SymbolTag>>,
+    pub resolve_support: Option<Properties>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RefreshClientCapabilities {
+    pub refresh_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FileOperationClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub did_create: Option<bool>,
+    pub will_create: Option<bool>,
+    pub did_rename: Option<bool>,
+    pub will_rename: Option<bool>,
+    pub did_delete: Option<bool>,
+    pub will_delete: Option<bool>,
+}
+
+// Text document.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentClientCapabilities {
+    pub synchronization: Option<TextDocumentSyncClientCapabilities>,
+    pub completion: Option<CompletionClientCapabilities>,
+    pub hover: Option<HoverClientCapabilities>,
+    pub signature_help: Option<SignatureHelpClientCapabilities>,
+    pub declaration: Option<GotoClientCapabilities>,
+    pub definition: Option<GotoClientCapabilities>,
+    pub type_definition: Option<GotoClientCapabilities>,
+    pub implementation: Option<GotoClientCapabilities>,
+    pub references: Option<DynamicRegistrationCapabilities>,
+    pub document_highlight: Option<DynamicRegistrationCapabilities>,
+    pub document_symbol: Option<DocumentSymbolClientCapabilities>,
+    pub code_action: Option<CodeActionClientCapabilities>,
+    pub code_lens: Option<DynamicRegistrationCapabilities>,
+    pub document_link: Option<DocumentLinkClientCapabilities>,
+    pub color_provider: Option<DynamicRegistrationCapabilities>,
+    pub formatting: Option<DynamicRegistrationCapabilities>,
+    pub range_formatting: Option<DynamicRegistrationCapabilities>,
+    pub on_type_formatting: Option<DynamicRegistrationCapabilities>,
+    pub rename: Option<RenameClientCapabilities>,
+    pub publish_diagnostics: Option<PublishDiagnosticsClientCapabilities>,
+    pub folding_range: Option<FoldingRangeClientCapabilities>,
+    pub selection_range: Option<DynamicRegistrationCapabilities>,
+    pub linked_editing_range: Option<DynamicRegistrationCapabilities>,
+    pub call_hierarchy: Option<DynamicRegistrationCapabilities>,
+    pub semantic_tokens: Option<SemanticTokensClientCapabilities>,
+    pub moniker: Option<DynamicRegistrationCapabilities>,
+    pub type_hierarchy: Option<DynamicRegistrationCapabilities>,
+    pub inline_value: Option<DynamicRegistrationCapabilities>,
+    pub inlay_hint: Option<InlayHintClientCapabilities>,
+    pub diagnostic: Option<DiagnosticClientCapabilities>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentSyncClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub will_save: Option<bool>,
+    pub will_save_wait_until: Option<bool>,
+    pub did_save: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub completion_item: Option<CompletionItemClientCapabilities>,
+    pub completion_item_kind: Option<ValueSet<CompletionItemKind>>,
+    #[serde(default, deserialize_with="lenient_one")]
+    pub insert_text_mode: Option<InsertTextMode>,
+    pub context_support: Option<bool>,
+    pub completion_list: Option<CompletionListClientCapabilities>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionItemClientCapabilities {
+    pub snippet_support: Option<bool>,
+    pub commit_characters_support: Option<bool>,
+    #[serde(default, deserialize_with="lenient_option")]
+    pub documentation_format: Option<Vec<MarkupKind>>,
+    pub deprecated_support: Option<bool>,
+    pub preselect_support: Option<bool>,
+    pub tag_support: Option<ValueSet<CompletionItemTag>>,
+    pub insert_replace_support: Option<bool>,
+    pub resolve_support: Option<Properties>,
+    pub insert_text_mode_support: Option<ValueSet<InsertTextMode>>,
+    pub label_details_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionListClientCapabilities {
+    pub item_defaults: Option<Vec<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct HoverClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    #[serde(default, deserialize_with="lenient_option")]
+    pub content_format: Option<Vec<MarkupKind>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureHelpClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub signature_information: Option<SignatureInformationClientCapabilities>,
+    pub context_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ParameterInformationClientCapabilities {
+    pub label_offset_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureInformationClientCapabilities {
+    #[serde(default, deserialize_with="lenient_option")]
+    pub documentation_format: Option<Vec<MarkupKind>>,
+    pub parameter_information: Option<ParameterInformationClientCapabilities>,
+    pub active_parameter_support: Option<bool>,
+}
+
+/// Capabilities of declaration, definition, type definition and
+/// implementation requests.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct GotoClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub link_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentSymbolClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub symbol_kind: Option<ValueSet<SymbolKind>>,
+    pub hierarchical_document_symbol_support: Option<bool>,
+    pub tag_support: Option<ValueSet<SymbolTag>>,
+    pub label_support: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CodeActionLiteralSupport {
+    pub code_action_kind: ValueSet<CodeActionKind>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CodeActionClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub code_action_literal_support: Option<CodeActionLiteralSupport>,
+    pub is_preferred_support: Option<bool>,
+    pub disabled_support: Option<bool>,
+    pub data_support: Option<bool>,
+    pub resolve_support: Option<Properties>,
+    pub honors_change_annotations: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentLinkClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub tooltip_support: Option<bool>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum PrepareSupportDefaultBehavior {
+    Identifier = 1,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RenameClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub prepare_support: Option<bool>,
+    #[serde(default, deserialize_with="lenient_one")]
+    pub prepare_support_default_behavior: Option<PrepareSupportDefaultBehavior>,
+    pub honors_change_annotations: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct PublishDiagnosticsClientCapabilities {
+    pub related_information: Option<bool>,
+    pub tag_support: Option<ValueSet<DiagnosticTag>>,
+    pub version_support: Option<bool>,
+    pub code_description_support: Option<bool>,
+    pub data_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FoldingRangeKindCapabilities {
+    pub value_set: Option<Vec<FoldingRangeKind>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FoldingRangeCapabilities {
+    pub collapsed_text: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FoldingRangeClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub range_limit: Option<u32>,
+    pub line_folding_only: Option<bool>,
+    pub folding_range_kind: Option<FoldingRangeKindCapabilities>,
+    pub folding_range: Option<FoldingRangeCapabilities>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensRequestsCapabilities {
+    pub range: Option<Either<bool, Value>>,
+    pub full: Option<Either<bool, SemanticTokensFullCapabilities>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensFullCapabilities {
+    pub delta: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub requests: SemanticTokensRequestsCapabilities,
+    pub token_types: Vec<String>,
+    pub token_modifiers: Vec<String>,
+    #[serde(deserialize_with="lenient")]
+    pub formats: Vec<TokenFormat>,
+    pub overlapping_token_support: Option<bool>,
+    pub multiline_token_support: Option<bool>,
+    pub server_cancel_support: Option<bool>,
+    pub augments_syntax_tokens: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InlayHintClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub resolve_support: Option<Properties>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DiagnosticClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub related_document_support: Option<bool>,
+}
+
+// Window and general.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct MessageActionItemCapabilities {
+    pub additional_properties_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ShowMessageRequestClientCapabilities {
+    pub message_action_item: Option<MessageActionItemCapabilities>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ShowDocumentClientCapabilities {
+    pub support: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WindowClientCapabilities {
+    pub work_done_progress: Option<bool>,
+    pub show_message: Option<ShowMessageRequestClientCapabilities>,
+    pub show_document: Option<ShowDocumentClientCapabilities>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct StaleRequestSupport {
+    pub cancel: bool,
+    pub retry_on_content_modified: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct GeneralClientCapabilities {
+    pub stale_request_support: Option<StaleRequestSupport>,
+    pub regular_expressions: Option<RegularExpressionsClientCapabilities>,
+    pub markdown: Option<MarkdownClientCapabilities>,
+    pub position_encodings: Option<Vec<PositionEncodingKind>>,
+}
+
+// Server.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ServerCapabilities {
+    pub position_encoding: Option<PositionEncodingKind>,
+    pub text_document_sync: Option<Either<TextDocumentSyncOptions, TextDocumentSyncKind>>,
+    pub notebook_document_sync: Option<Value>,
+    pub completion_provider: Option<CompletionOptions>,
+    pub hover_provider: Option<Either<bool, WorkDoneProgressOptions>>,
+    pub signature_help_provider: Option<SignatureHelpOptions>,
+    pub declaration_provider: Option<Either<bool, RegistrationOptions>>,
+    pub definition_provider: Option<Either<bool, WorkDoneProgressOptions>>,
+    pub type_definition_provider: Option<Either<bool, RegistrationOptions>>,
+    pub implementation_provider: Option<Either<bool, RegistrationOptions>>,
+    pub references_provider: Option<Either<bool, WorkDoneProgressOptions>>,
+    pub document_highlight_provider: Option<Either<bool, WorkDoneProgressOptions>>,
+    pub document_symbol_provider: Option<Either<bool, DocumentSymbolOptions>>,
+    pub code_action_provider: Option<Either<bool, CodeActionOptions>>,
+    pub code_lens_provider: Option<ResolveOptions>,
+    pub document_link_provider: Option<ResolveOptions>,
+    pub color_provider: Option<Either<bool, RegistrationOptions>>,
+    pub document_formatting_provider: Option<Either<bool, WorkDoneProgressOptions>>,
+    pub document_range_formatting_provider: Option<Either<bool, WorkDoneProgressOptions>>,
+    pub document_on_type_formatting_provider: Option<DocumentOnTypeFormattingOptions>,
+    pub rename_provider: Option<Either<bool, RenameOptions>>,
+    pub folding_range_provider: Option<Either<bool, RegistrationOptions>>,
+    pub execute_command_provider: Option<ExecuteCommandOptions>,
+    pub selection_range_provider: Option<Either<bool, RegistrationOptions>>,
+    pub linked_editing_range_provider: Option<Either<bool, RegistrationOptions>>,
+    pub call_hierarchy_provider: Option<Either<bool, RegistrationOptions>>,
+    pub semantic_tokens_provider: Option<SemanticTokensOptions>,
+    pub moniker_provider: Option<Either<bool, RegistrationOptions>>,
+    pub type_hierarchy_provider: Option<Either<bool, RegistrationOptions>>,
+    pub inline_value_provider: Option<Either<bool, RegistrationOptions>>,
+    pub inlay_hint_provider: Option<Either<bool, ResolveOptions>>,
+    pub diagnostic_provider: Option<DiagnosticOptions>,
+    pub workspace_symbol_provider: Option<Either<bool, ResolveOptions>>,
+    pub workspace: Option<WorkspaceServerCapabilities>,
+    pub experimental: Option<Value>,
+}
+
+/// Options of providers whose items can be resolved lazily.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ResolveOptions {
+    pub resolve_provider: Option<bool>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionItemOptions {
+    pub label_details_support: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionOptions {
+    pub trigger_characters: Option<Vec<String>>,
+    pub all_commit_characters: Option<Vec<String>>,
+    pub resolve_provider: Option<bool>,
+    pub completion_item: Option<CompletionItemOptions>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureHelpOptions {
+    pub trigger_characters: Option<Vec<String>>,
+    pub retrigger_characters: Option<Vec<String>>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentSymbolOptions {
+    pub label: Option<String>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CodeActionOptions {
+    pub code_action_kinds: Option<Vec<CodeActionKind>>,
+    pub resolve_provider: Option<bool>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentOnTypeFormattingOptions {
+    pub first_trigger_character: String,
+    pub more_trigger_character: Option<Vec<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RenameOptions {
+    pub prepare_provider: Option<bool>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ExecuteCommandOptions {
+    pub commands: Vec<String>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensOptions {
+    pub legend: SemanticTokensLegend,
+    pub range: Option<Either<bool, Value>>,
+    pub full: Option<Either<bool, SemanticTokensFullCapabilities>>,
+    pub work_done_progress: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DiagnosticOptions {
+    pub identifier: Option<String>,
+    pub inter_file_dependencies: bool,
+    pub workspace_diagnostics: bool,
+    pub work_done_progress: Option<bool>,
+    #[serde(flatten)]
+    pub static_registration_options: StaticRegistrationOptions,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceFoldersServerCapabilities {
+    pub supported: Option<bool>,
+    pub change_notifications: Option<Either<bool, String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FileOperationServerCapabilities {
+    pub did_create: Option<FileOperationRegistrationOptions>,
+    pub will_create: Option<FileOperationRegistrationOptions>,
+    pub did_rename: Option<FileOperationRegistrationOptions>,
+    pub will_rename: Option<FileOperationRegistrationOptions>,
+    pub did_delete: Option<FileOperationRegistrationOptions>,
+    pub will_delete: Option<FileOperationRegistrationOptions>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceServerCapabilities {
+    pub workspace_folders: Option<WorkspaceFoldersServerCapabilities>,
+    pub file_operations: Option<FileOperationServerCapabilities>,
+}
+
@@ -0,0 +1,982 @@
+//! Language features.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_with::skip_serializing_none;
+
+use super::{
+    Command, Diagnostic, DocumentUri, Either, InsertTextMode, Location, LocationLink, MarkedString, MarkupContent,
+    PartialResultParams, Position, Range, TextDocumentIdentifier, TextDocumentPositionParams, TextEdit,
+    WorkDoneProgressParams, WorkspaceEdit,
+};
+
+pub type CodeActionKind = String;
+pub type FoldingRangeKind = String;
+
+pub mod code_action_kind {
+    pub const EMPTY: &str = "";
+    pub const QUICKFIX: &str = "quickfix";
+    pub const REFACTOR: &str = "refactor";
+    pub const REFACTOR_EXTRACT: &str = "refactor.extract";
+    pub const REFACTOR_INLINE: &str = "refactor.inline";
+    pub const REFACTOR_REWRITE: &str = "refactor.rewrite";
+    pub const SOURCE: &str = "source";
+    pub const SOURCE_ORGANIZE_IMPORTS: &str = "source.organizeImports";
+    pub const SOURCE_FIX_ALL: &str = "source.fixAll";
+}
+
+pub mod folding_range_kind {
+    pub const COMMENT: &str = "comment";
+    pub const IMPORTS: &str = "imports";
+    pub const REGION: &str = "region";
+}
+
+/// Parameters of requests taking a document and a position, such as
+/// `textDocument/definition` or `textDocument/hover`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct PositionParams {
+    #[serde(flatten)]
+    pub text_document_position_params: TextDocumentPositionParams,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+pub type DeclarationParams = PositionParams;
+pub type DefinitionParams = PositionParams;
+pub type TypeDefinitionParams = PositionParams;
+pub type ImplementationParams = PositionParams;
+pub type DocumentHighlightParams = PositionParams;
+pub type HoverParams = PositionParams;
+pub type PrepareRenameParams = PositionParams;
+pub type LinkedEditingRangeParams = PositionParams;
+pub type CallHierarchyPrepareParams = PositionParams;
+pub type TypeHierarchyPrepareParams = PositionParams;
+pub type MonikerParams = PositionParams;
+
+/// Parameters of requests only taking a document, such as
+/// `textDocument/documentSymbol`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+pub type DocumentSymbolParams = DocumentParams;
+pub type CodeLensParams = DocumentParams;
+pub type DocumentLinkParams = DocumentParams;
+pub type DocumentColorParams = DocumentParams;
+pub type FoldingRangeParams = DocumentParams;
+pub type SemanticTokensParams = DocumentParams;
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct PublishDiagnosticsParams {
+    pub uri: DocumentUri,
+    pub version: Option<i32>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// Go to declaration, definition, type definition, implementation.
+
+/// Result of the `textDocument/definition` family of requests.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum GotoResult {
+    Location(Location),
+    Locations(Vec<Location>),
+    Links(Vec<LocationLink>),
+}
+
+// Find references.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ReferenceContext {
+    pub include_declaration: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ReferenceParams {
+    #[serde(flatten)]
+    pub position_params: PositionParams,
+    pub context: ReferenceContext,
+}
+
+// Call hierarchy.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub tags: Option<Vec<SymbolTag>>,
+    pub detail: Option<String>,
+    pub uri: DocumentUri,
+    pub range: Range,
+    pub selection_range: Range,
+    pub data: Option<Value>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CallHierarchyCallsParams {
+    pub item: CallHierarchyItem,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+pub type CallHierarchyIncomingCallsParams = CallHierarchyCallsParams;
+pub type CallHierarchyOutgoingCallsParams = CallHierarchyCallsParams;
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CallHierarchyIncomingCall {
+    pub from: CallHierarchyItem,
+    pub from_ranges: Vec<Range>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CallHierarchyOutgoingCall {
+    pub to: CallHierarchyItem,
+    pub from_ranges: Vec<Range>,
+}
+
+// Type hierarchy.
+
+pub type TypeHierarchyItem = CallHierarchyItem;
+pub type TypeHierarchySupertypesParams = CallHierarchyCallsParams;
+pub type TypeHierarchySubtypesParams = CallHierarchyCallsParams;
+
+// Document highlights.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum DocumentHighlightKind {
+    Text = 1,
+    Read = 2,
+    Write = 3,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DocumentHighlight {
+    pub range: Range,
+    pub kind: Option<DocumentHighlightKind>,
+}
+
+// Document links.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DocumentLink {
+    pub range: Range,
+    pub target: Option<DocumentUri>,
+    pub tooltip: Option<String>,
+    pub data: Option<Value>,
+}
+
+// Hover.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Hover {
+    pub contents: HoverContents,
+    pub range: Option<Range>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum HoverContents {
+    Markup(MarkupContent),
+    Marked(MarkedString),
+    MarkedList(Vec<MarkedString>),
+}
+
+// Code lens.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CodeLens {
+    pub range: Range,
+    pub command: Option<Command>,
+    pub data: Option<Value>,
+}
+
+// Folding ranges.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FoldingRange {
+    pub start_line: u32,
+    pub start_character: Option<u32>,
+    pub end_line: u32,
+    pub end_character: Option<u32>,
+    pub kind: Option<FoldingRangeKind>,
+    pub collapsed_text: Option<String>,
+}
+
+// Selection ranges.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SelectionRangeParams {
+    pub text_document: TextDocumentIdentifier,
+    pub positions: Vec<Position>,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct SelectionRange {
+    pub range: Range,
+    pub parent: Option<Box<SelectionRange>>,
+}
+
+// Document symbols.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum SymbolKind {
+    File = 1,
+    Module = 2,
+    Namespace = 3,
+    Package = 4,
+    Class = 5,
+    Method = 6,
+    Property = 7,
+    Field = 8,
+    Constructor = 9,
+    Enum = 10,
+    Interface = 11,
+    Function = 12,
+    Variable = 13,
+    Constant = 14,
+    String = 15,
+    Number = 16,
+    Boolean = 17,
+    Array = 18,
+    Object = 19,
+    Key = 20,
+    Null = 21,
+    EnumMember = 22,
+    Struct = 23,
+    Event = 24,
+    Operator = 25,
+    TypeParameter = 26,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum SymbolTag {
+    Deprecated = 1,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub detail: Option<String>,
+    pub kind: SymbolKind,
+    pub tags: Option<Vec<SymbolTag>>,
+    /// Deprecated in favour of `tags`.
+    pub deprecated: Option<bool>,
+    pub range: Range,
+    pub selection_range: Range,
+    pub children: Option<Vec<DocumentSymbol>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SymbolInformation {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub tags: Option<Vec<SymbolTag>>,
+    pub deprecated: Option<bool>,
+    pub location: Location,
+    pub container_name: Option<String>,
+}
+
+// Semantic tokens.
+
+pub mod semantic_token_types {
+    pub const NAMESPACE: &str = "namespace";
+    pub const TYPE: &str = "type";
+    pub const CLASS: &str = "class";
+    pub const ENUM: &str = "enum";
+    pub const INTERFACE: &str = "interface";
+    pub const STRUCT: &str = "struct";
+    pub const TYPE_PARAMETER: &str = "typeParameter";
+    pub const PARAMETER: &str = "parameter";
+    pub const VARIABLE: &str = "variable";
+    pub const PROPERTY: &str = "property";
+    pub const ENUM_MEMBER: &str = "enumMember";
+    pub const EVENT: &str = "event";
+    pub const FUNCTION: &str = "function";
+    pub const METHOD: &str = "method";
+    pub const MACRO: &str = "macro";
+    pub const KEYWORD: &str = "keyword";
+    pub const MODIFIER: &str = "modifier";
+    pub const COMMENT: &str = "comment";
+    pub const STRING: &str = "string";
+    pub const NUMBER: &str = "number";
+    pub const REGEXP: &str = "regexp";
+    pub const OPERATOR: &str = "operator";
+    pub const DECORATOR: &str = "decorator";
+}
+
+pub mod semantic_token_modifiers {
+    pub const DECLARATION: &str = "declaration";
+    pub const DEFINITION: &str = "definition";
+    pub const READONLY: &str = "readonly";
+    pub const STATIC: &str = "static";
+    pub const DEPRECATED: &str = "deprecated";
+    pub const ABSTRACT: &str = "abstract";
+    pub const ASYNC: &str = "async";
+    pub const MODIFICATION: &str = "modification";
+    pub const DOCUMENTATION: &str = "documentation";
+    pub const DEFAULT_LIBRARY: &str = "defaultLibrary";
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum TokenFormat {
+    Relative,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensLegend {
+    pub token_types: Vec<String>,
+    pub token_modifiers: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensPartialResult {
+    pub data: Vec<u32>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensDeltaParams {
+    pub text_document: TextDocumentIdentifier,
+    pub previous_result_id: String,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensEdit {
+    pub start: u32,
+    pub delete_count: u32,
+    pub data: Option<Vec<u32>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensDelta {
+    pub result_id: Option<String>,
+    pub edits: Vec<SemanticTokensEdit>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SemanticTokensRangeParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+// Inlay hints.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RangeParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+pub type InlayHintParams = RangeParams;
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InlayHint {
+    pub position: Position,
+    pub label: Either<String, Vec<InlayHintLabelPart>>,
+    pub kind: Option<InlayHintKind>,
+    pub text_edits: Option<Vec<TextEdit>>,
+    pub tooltip: Option<Either<String, MarkupContent>>,
+    pub padding_left: Option<bool>,
+    pub padding_right: Option<bool>,
+    pub data: Option<Value>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InlayHintLabelPart {
+    pub value: String,
+    pub tooltip: Option<Either<String, MarkupContent>>,
+    pub location: Option<Location>,
+    pub command: Option<Command>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum InlayHintKind {
+    Type = 1,
+    Parameter = 2,
+}
+
+// Inline values.
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InlineValueContext {
+    pub frame_id: i32,
+    pub stopped_location: Range,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InlineValueParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    pub context: InlineValueContext,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct InlineValueText {
+    pub range: Range,
+    pub text: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InlineValueVariableLookup {
+    pub range: Range,
+    pub variable_name: Option<String>,
+    pub case_sensitive_lookup: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct InlineValueEvaluatableExpression {
+    pub range: Range,
+    pub expression: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum InlineValue {
+    Text(InlineValueText),
+    VariableLookup(InlineValueVariableLookup),
+    EvaluatableExpression(InlineValueEvaluatableExpression),
+}
+
+// Monikers.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum UniquenessLevel {
+    Document,
+    Project,
+    Group,
+    Scheme,
+    Global,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum MonikerKind {
+    Import,
+    Export,
+    Local,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Moniker {
+    pub scheme: String,
+    pub identifier: String,
+    pub unique: UniquenessLevel,
+    pub kind: Option<MonikerKind>,
+}
+
+// Completion.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum CompletionTriggerKind {
+    Invoked = 1,
+    TriggerCharacter = 2,
+    TriggerForIncompleteCompletions = 3,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionContext {
+    pub trigger_kind: CompletionTriggerKind,
+    pub trigger_character: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionParams {
+    #[serde(flatten)]
+    pub position_params: PositionParams,
+    pub context: Option<CompletionContext>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum CompletionItemKind {
+    Text = 1,
+    Method = 2,
+    Function = 3,
+    Constructor = 4,
+    Field = 5,
+    Variable = 6,
+    Class = 7,
+    Interface = 8,
+    Module = 9,
+    Property = 10,
+    Unit = 11,
+    Value = 12,
+    Enum = 13,
+    Keyword = 14,
+    Snippet = 15,
+    Color = 16,
+    File = 17,
+    Reference = 18,
+    Folder = 19,
+    EnumMember = 20,
+    Constant = 21,
+    Struct = 22,
+    Event = 23,
+    Operator = 24,
+    TypeParameter = 25,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum CompletionItemTag {
+    Deprecated = 1,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum InsertTextFormat {
+    PlainText = 1,
+    Snippet = 2,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionItemLabelDetails {
+    pub detail: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InsertReplaceEdit {
+    pub new_text: String,
+    pub insert: Range,
+    pub replace: Range,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    pub label_details: Option<CompletionItemLabelDetails>,
+    pub kind: Option<CompletionItemKind>,
+    pub tags: Option<Vec<CompletionItemTag>>,
+    pub detail: Option<String>,
+    pub documentation: Option<Either<String, MarkupContent>>,
+    pub deprecated: Option<bool>,
+    pub preselect: Option<bool>,
+    pub sort_text: Option<String>,
+    pub filter_text: Option<String>,
+    pub insert_text: Option<String>,
+    pub insert_text_format: Option<InsertTextFormat>,
+    pub insert_text_mode: Option<InsertTextMode>,
+    pub text_edit: Option<Either<TextEdit, InsertReplaceEdit>>,
+    pub text_edit_text: Option<String>,
+    pub additional_text_edits: Option<Vec<TextEdit>>,
+    pub commit_characters: Option<Vec<String>>,
+    pub command: Option<Command>,
+    pub data: Option<Value>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionItemDefaults {
+    pub commit_characters: Option<Vec<String>>,
+    pub edit_range: Option<Either<Range, InsertReplaceRange>>,
+    pub insert_text_format: Option<InsertTextFormat>,
+    pub insert_text_mode: Option<InsertTextMode>,
+    pub data: Option<Value>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct InsertReplaceRange {
+    pub insert: Range,
+    pub replace: Range,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CompletionList {
+    pub is_incomplete: bool,
+    pub item_defaults: Option<CompletionItemDefaults>,
+    pub items: Vec<CompletionItem>,
+}
+
+// Signature help.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum SignatureHelpTriggerKind {
+    Invoked = 1,
+    TriggerCharacter = 2,
+    ContentChange = 3,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureHelpContext {
+    pub trigger_kind: SignatureHelpTriggerKind,
+    pub trigger_character: Option<String>,
+    pub is_retrigger: bool,
+    pub active_signature_help: Option<SignatureHelp>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureHelpParams {
+    #[serde(flatten)]
+    pub text_document_position_params: TextDocumentPositionParams,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    pub context: Option<SignatureHelpContext>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    pub active_signature: Option<u32>,
+    pub active_parameter: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SignatureInformation {
+    pub label: String,
+    pub documentation: Option<Either<String, MarkupContent>>,
+    pub parameters: Option<Vec<ParameterInformation>>,
+    pub active_parameter: Option<u32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ParameterInformation {
+    /// Either a substring of the signature label or an offset range in it.
+    pub label: Either<String, [u32; 2]>,
+    pub documentation: Option<Either<String, MarkupContent>>,
+}
+
+// Code actions.
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum CodeActionTriggerKind {
+    Invoked = 1,
+    Automatic = 2,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CodeActionContext {
+    pub diagnostics: Vec<Diagnostic>,
+    pub only: Option<Vec<CodeActionKind>>,
+    pub trigger_kind: Option<CodeActionTriggerKind>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CodeActionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    pub context: CodeActionContext,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct CodeActionDisabled {
+    pub reason: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct CodeAction {
+    pub title: String,
+    pub kind: Option<CodeActionKind>,
+    pub diagnostics: Option<Vec<Diagnostic>>,
+    pub is_preferred: Option<bool>,
+    pub disabled: Option<CodeActionDisabled>,
+    pub edit: Option<WorkspaceEdit>,
+    pub command: Option<Command>,
+    pub data: Option<Value>,
+}
+
+// Colors.
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Color {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+    pub alpha: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ColorInformation {
+    pub range: Range,
+    pub color: Color,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ColorPresentationParams {
+    pub text_document: TextDocumentIdentifier,
+    pub color: Color,
+    pub range: Range,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ColorPresentation {
+    pub label: String,
+    pub text_edit: Option<TextEdit>,
+    pub additional_text_edits: Option<Vec<TextEdit>>,
+}
+
+// Formatting.
+
+/// Formatting options; properties other than the well known ones are
+/// kept in `properties`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FormattingOptions {
+    pub tab_size: u32,
+    pub insert_spaces: bool,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub trim_final_newlines: Option<bool>,
+    #[serde(flatten)]
+    pub properties: serde_json::Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentFormattingParams {
+    pub text_document: TextDocumentIdentifier,
+    pub options: FormattingOptions,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentRangeFormattingParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    pub options: FormattingOptions,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentOnTypeFormattingParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+    pub ch: String,
+    pub options: FormattingOptions,
+}
+
+// Rename.
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct RenameParams {
+    #[serde(flatten)]
+    pub text_document_position_params: TextDocumentPositionParams,
+    pub new_name: String,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PrepareRenameResult {
+    Range(Range),
+    RangeWithPlaceholder { range: Range, placeholder: String },
+    #[serde(rename_all="camelCase")]
+    DefaultBehavior { default_behavior: bool },
+}
+
+// Linked editing ranges.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct LinkedEditingRanges {
+    pub ranges: Vec<Range>,
+    pub word_pattern: Option<String>,
+}
+
+// Pull diagnostics.
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DocumentDiagnosticParams {
+    pub text_document: TextDocumentIdentifier,
+    pub identifier: Option<String>,
+    pub previous_result_id: Option<String>,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct FullDocumentDiagnosticReport {
+    pub result_id: Option<String>,
+    pub items: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct UnchangedDocumentDiagnosticReport {
+    pub result_id: String,
+}
+
+/// Report of `textDocument/diagnostic`, tagged by its `kind`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag="kind", rename_all="lowercase")]
+pub enum DocumentDiagnosticReport {
+    Full(FullDocumentDiagnosticReport),
+    Unchanged(UnchangedDocumentDiagnosticReport),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DiagnosticServerCancellationData {
+    pub retrigger_request: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct PreviousResultId {
+    pub uri: DocumentUri,
+    pub value: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceDiagnosticParams {
+    pub identifier: Option<String>,
+    pub previous_result_ids: Vec<PreviousResultId>,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WorkspaceDocumentDiagnosticReport {
+    #[serde(flatten)]
+    pub report: DocumentDiagnosticReport,
+    pub uri: DocumentUri,
+    pub version: Option<i32>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WorkspaceDiagnosticReport {
+    pub items: Vec<WorkspaceDocumentDiagnosticReport>,
+}
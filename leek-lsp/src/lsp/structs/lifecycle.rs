@@ -0,0 +1,108 @@
+//! Lifecycle messages: initialization, registration and tracing.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::skip_serializing_none;
+
+use super::{ClientCapabilities, DocumentUri, ServerCapabilities, TraceValue, Uri, WorkDoneProgressParams};
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ClientInfo {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ServerInfo {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Parameters of the `initialize` request, `T` being the type of the
+/// `initializationOptions`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InitializeParams<T = Value> {
+    #[serialize_always]
+    pub process_id: Option<i32>,
+    pub client_info: Option<ClientInfo>,
+    pub locale: Option<String>,
+    /// Deprecated in favour of `root_uri`.
+    pub root_path: Option<String>,
+    /// Deprecated in favour of `workspace_folders`.
+    #[serialize_always]
+    pub root_uri: Option<DocumentUri>,
+    pub initialization_options: Option<T>,
+    pub capabilities: ClientCapabilities,
+    pub trace: Option<TraceValue>,
+    pub workspace_folders: Option<Vec<WorkspaceFolder>>,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InitializeResult {
+    pub capabilities: ServerCapabilities,
+    pub server_info: Option<ServerInfo>,
+}
+
+/// Data of the error returned by a failed `initialize`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct InitializeError {
+    pub retry: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct InitializedParams {}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct WorkspaceFolder {
+    pub uri: Uri,
+    pub name: String,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct Registration {
+    pub id: String,
+    pub method: String,
+    pub register_options: Option<Value>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct RegistrationParams {
+    pub registrations: Vec<Registration>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Unregistration {
+    pub id: String,
+    pub method: String,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct UnregistrationParams {
+    /// The misspelling is part of the specification.
+    pub unregisterations: Vec<Unregistration>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct SetTraceParams {
+    pub value: TraceValue,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct LogTraceParams {
+    pub message: String,
+    pub verbose: Option<String>,
+}
@@ -0,0 +1,66 @@
+//! Types of the Language Server Protocol, version 3.17.
+//!
+//! The modules follow the chapters of the specification. Optional
+//! properties are omitted when serializing, while properties typed as
+//! `T | null` in the specification are always written.
+//!
+//! Enumerations sent by the client in its capabilities (kinds, formats...)
+//! may contain values added by later versions of the protocol: they are
+//! deserialized leniently and unknown values are dropped.
+
+mod basic;
+mod capabilities;
+mod language;
+mod lifecycle;
+mod sync;
+mod window;
+mod workspace;
+
+pub use basic::*;
+pub use capabilities::*;
+pub use language::*;
+pub use lifecycle::*;
+pub use sync::*;
+pub use window::*;
+pub use workspace::*;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// Deserialize a list of enumeration values, skipping unknown values.
+fn lenient<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Vec::<Value>::deserialize(deserializer)?;
+    Ok(values.into_iter().filter_map(|value| serde_json::from_value(value).ok()).collect())
+}
+
+/// Same as [`lenient`] for optional lists.
+fn lenient_option<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Option::<Vec<Value>>::deserialize(deserializer)?;
+    Ok(values.map(|values| values.into_iter().filter_map(|value| serde_json::from_value(value).ok()).collect()))
+}
+
+/// Deserialize an optional enumeration value, ignoring unknown values.
+fn lenient_one<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| serde_json::from_value(value).ok()))
+}
@@ -0,0 +1,107 @@
+//! Text document synchronization.
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_with::skip_serializing_none;
+
+use super::{
+    Either, Range, RegistrationOptions, TextDocumentIdentifier, TextDocumentItem, VersionedTextDocumentIdentifier,
+};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum TextDocumentSyncKind {
+    #[default]
+    None = 0,
+    Full = 1,
+    Incremental = 2,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct SaveOptions {
+    pub include_text: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentSyncOptions {
+    pub open_close: Option<bool>,
+    pub change: Option<TextDocumentSyncKind>,
+    pub will_save: Option<bool>,
+    pub will_save_wait_until: Option<bool>,
+    pub save: Option<Either<bool, SaveOptions>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DidOpenTextDocumentParams {
+    pub text_document: TextDocumentItem,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentContentChangeEvent {
+    /// Replace the whole document when missing.
+    pub range: Option<Range>,
+    /// Deprecated, `range` is authoritative.
+    pub range_length: Option<u32>,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DidChangeTextDocumentParams {
+    pub text_document: VersionedTextDocumentIdentifier,
+    pub content_changes: Vec<TextDocumentContentChangeEvent>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentChangeRegistrationOptions {
+    #[serde(flatten)]
+    pub registration_options: RegistrationOptions,
+    pub sync_kind: TextDocumentSyncKind,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum TextDocumentSaveReason {
+    Manual = 1,
+    AfterDelay = 2,
+    FocusOut = 3,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct WillSaveTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    pub reason: TextDocumentSaveReason,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DidSaveTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    /// Present when the server asked for it with `include_text`.
+    pub text: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct TextDocumentSaveRegistrationOptions {
+    #[serde(flatten)]
+    pub registration_options: RegistrationOptions,
+    pub include_text: Option<bool>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct DidCloseTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+}
@@ -0,0 +1,75 @@
+//! Window features: messages, documents shown by the client and progress.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_with::skip_serializing_none;
+
+use super::{ProgressToken, Range, Uri};
+
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize_repr, Serialize_repr)]
+#[repr(u8)]
+pub enum MessageType {
+    Error = 1,
+    Warning = 2,
+    Info = 3,
+    Log = 4,
+    /// Since 3.18, clients may already send it.
+    Debug = 5,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ShowMessageParams {
+    #[serde(rename="type")]
+    pub typ: MessageType,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MessageActionItem {
+    pub title: String,
+    /// Additional properties are sent back to the server untouched.
+    #[serde(flatten)]
+    pub properties: serde_json::Map<String, Value>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ShowMessageRequestParams {
+    #[serde(rename="type")]
+    pub typ: MessageType,
+    pub message: String,
+    pub actions: Option<Vec<MessageActionItem>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ShowDocumentParams {
+    pub uri: Uri,
+    pub external: Option<bool>,
+    pub take_focus: Option<bool>,
+    pub selection: Option<Range>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ShowDocumentResult {
+    pub success: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct LogMessageParams {
+    #[serde(rename="type")]
+    pub typ: MessageType,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WorkDoneProgressCreateParams {
+    pub token: ProgressToken,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct WorkDoneProgressCancelParams {
+    pub token: ProgressToken,
+}
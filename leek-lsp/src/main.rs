@@ -1,43 +1,51 @@
-#[macro_use]
-extern crate serde;
-
-mod lsp;
-
-#[macro_use]
-extern crate jsonrpc_derive;
-
-use jsonrpc_core::{Result as RPCResult, IoHandler};
-use jsonrpc_stdio_server::ServerBuilder;
-use crate::lsp::{LSP, InitalizeParams, InitializeResult, ServerCapabilities};
+use leek_lsp::cli::{self, Command};
+use leek_lsp::client::Client;
+use leek_lsp::logging;
+use leek_lsp::scheduler::Scheduler;
+use leek_lsp::server::LeekLSP;
+use leek_lsp::transport;
+use log::error;
 use std::process::exit;
 
-#[derive(Copy, Clone, Default, Debug)]
-pub struct LeekLSP;
-
-impl LSP for LeekLSP {
-    fn initialize(_params: InitalizeParams<(), ()>) -> RPCResult<InitializeResult<()>> {
-        Ok(InitializeResult {
-            capabilities: ServerCapabilities {
-                ..Default::default()
-            }
-        })
+pub fn main() {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Serve(options)) => options,
+        Ok(Command::Help) => return print!("{}", cli::USAGE),
+        Ok(Command::Version) => return println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        Ok(Command::PrintCapabilities) => {
+            let (sender, _) = transport::outgoing();
+            let capabilities = LeekLSP::new(Client::new(sender)).server_capabilities();
+            return println!("{}", serde_json::to_string_pretty(&capabilities).unwrap());
+        }
+        Err(err) => {
+            eprintln!("leek-lsp: {}\n\n{}", err, cli::USAGE);
+            exit(2);
+        }
+    };
+    let defaults = match options.config.as_deref().map(cli::read_config).transpose() {
+        Ok(defaults) => defaults,
+        Err(err) => {
+            eprintln!("leek-lsp: {}", err);
+            exit(2);
+        }
+    };
+
+    let (sender, outgoing) = transport::outgoing();
+    let client = Client::new(sender);
+    if let Err(err) = logging::init(options.log_level, options.log_file.as_deref()) {
+        eprintln!("leek-lsp: cannot initialize logging: {}", err);
     }
+    logging::attach_client(client.clone());
 
-    fn shutdown() -> RPCResult<()> {
-        println!("Shutdown requested");
-        Ok(())
+    let server = LeekLSP::new(client);
+    if let Some(defaults) = defaults {
+        server.set_default_settings(defaults);
     }
-
-    fn exit() -> RPCResult<()> {
-        println!("Exit requested");
-        exit(0)
+    let lifecycle = server.lifecycle().clone();
+    let transport = options.transport;
+    if let Err(err) = transport.serve(server.scheduler(Scheduler::default_workers()), outgoing) {
+        error!("cannot serve on {:?}: {}", transport, err);
     }
-}
-
-pub fn main() {
-    let mut io = IoHandler::new();
-    io.extend_with(LeekLSP.to_delegate());
-
-    ServerBuilder::new(io)
-        .build();
+    // The client went away without sending `exit`.
+    exit(lifecycle.exit_code());
 }
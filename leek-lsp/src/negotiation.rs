@@ -0,0 +1,99 @@
+//! Features negotiated with the client.
+//!
+//! Responses are always built with the richest shapes and downgraded here
+//! to what the client announced in its capabilities.
+
+use crate::lsp::*;
+
+/// What the client supports, extracted once from its capabilities.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Negotiated {
+    pub related_information: bool,
+    pub diagnostic_tags: Vec<DiagnosticTag>,
+    pub code_description: bool,
+    pub work_done_progress: bool,
+}
+
+/// Without capabilities, the client is assumed to support the protocol
+/// basics only.
+impl Default for Negotiated {
+    fn default() -> Self {
+        Negotiated::new(&ClientCapabilities::default())
+    }
+}
+
+impl Negotiated {
+    pub fn new(capabilities: &ClientCapabilities) -> Self {
+        let text_document = capabilities.text_document.clone().unwrap_or_default();
+        let diagnostics = text_document.publish_diagnostics.unwrap_or_default();
+        Negotiated {
+            related_information: diagnostics.related_information.unwrap_or(false),
+            diagnostic_tags: diagnostics.tag_support.map(|tags| tags.value_set).unwrap_or_default(),
+            code_description: diagnostics.code_description_support.unwrap_or(false),
+            work_done_progress: capabilities
+                .window
+                .as_ref()
+                .and_then(|window| window.work_done_progress)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Drop the parts of `diagnostics` the client does not understand.
+    pub fn diagnostics(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .map(|mut diagnostic| {
+                if !self.related_information {
+                    diagnostic.related_information = None;
+                }
+                if !self.code_description {
+                    diagnostic.code_description = None;
+                }
+                diagnostic.tags = diagnostic
+                    .tags
+                    .map(|tags| tags.into_iter().filter(|tag| self.diagnostic_tags.contains(tag)).collect::<Vec<_>>())
+                    .filter(|tags| !tags.is_empty());
+                diagnostic
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn negotiate(capabilities: serde_json::Value) -> Negotiated {
+        Negotiated::new(&serde_json::from_value(capabilities).unwrap())
+    }
+
+    fn range(line: u32) -> Range {
+        Range {
+            start: Position { line, character: 0 },
+            end: Position { line, character: 4 },
+        }
+    }
+
+    #[test]
+    fn diagnostic_features() {
+        let diagnostic = Diagnostic {
+            range: range(0),
+            severity: Some(DiagnosticSeverity::Warning),
+            code: None,
+            code_description: None,
+            source: None,
+            message: "unused".to_string(),
+            tags: Some(vec![DiagnosticTag::Unnecessary]),
+            related_information: Some(Vec::new()),
+            data: None,
+        };
+        let stripped = Negotiated::default().diagnostics(vec![diagnostic.clone()]);
+        assert_eq!((stripped[0].tags.clone(), stripped[0].related_information.clone()), (None, None));
+
+        let negotiated = negotiate(json!({ "textDocument": { "publishDiagnostics": {
+            "relatedInformation": true, "tagSupport": { "valueSet": [1] },
+        } } }));
+        assert_eq!(negotiated.diagnostics(vec![diagnostic.clone()]), vec![diagnostic]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Catching panics of the handlers, so that a bug in one feature does not
+//! take the whole server down.
+
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+/// A caught panic.
+#[derive(Debug)]
+pub struct Panic {
+    /// Panic message, with the location of the panic.
+    pub message: String,
+    pub backtrace: String,
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}", self.message, self.backtrace)
+    }
+}
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static CAUGHT: RefCell<Option<Panic>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// Record the panics happening in [`catch`] instead of printing them; other
+/// panics are left to the previous hook.
+fn install_hook() {
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(Cell::get) {
+                return previous(info);
+            }
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            let message = match info.location() {
+                Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+                None => message.to_string(),
+            };
+            let backtrace = Backtrace::force_capture().to_string();
+            CAUGHT.with(|caught| *caught.borrow_mut() = Some(Panic { message, backtrace }));
+        }));
+    });
+}
+
+/// Run `f`, turning a panic into an error.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Panic> {
+    install_hook();
+    let catching = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|current| current.set(catching));
+    result.map_err(|_| {
+        CAUGHT.with(|caught| caught.borrow_mut().take()).unwrap_or_else(|| Panic {
+            message: "unknown panic".to_string(),
+            backtrace: String::new(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catch_panics() {
+        assert_eq!(catch(|| 42).unwrap(), 42);
+        let panic = catch(|| -> u32 { panic!("bug in {}", "hover") }).unwrap_err();
+        assert!(panic.message.starts_with("bug in hover at "), "{}", panic.message);
+        assert!(panic.message.contains("panics.rs"));
+        // Nested catches report to the innermost one.
+        let outer = catch(|| catch(|| -> u32 { panic!("inner") }).unwrap_err().message);
+        assert!(outer.unwrap().starts_with("inner"));
+    }
+}
@@ -0,0 +1,74 @@
+//! Work done progress reported to the client, for long running tasks
+//! started by the server itself.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::client::Client;
+use crate::lsp::{
+    Either, ProgressParams, ProgressToken, WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressCreateParams,
+    WorkDoneProgressEnd, WorkDoneProgressReport,
+};
+
+/// How long to wait for the client to accept a new progress.
+const CREATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+/// A progress shown by the client, or nothing when the client does not
+/// support work done progress.
+pub struct Progress {
+    client: Client,
+    token: Option<ProgressToken>,
+}
+
+impl Progress {
+    /// Ask the client to create a progress titled `title` and begin it.
+    ///
+    /// This waits for the client to answer, it must not be called from the
+    /// transport thread.
+    pub fn begin(client: &Client, supported: bool, title: &str) -> Progress {
+        let mut progress = Progress { client: client.clone(), token: None };
+        if !supported {
+            return progress;
+        }
+        let token = Either::Right(format!("leek-lsp/{}", NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)));
+        let (sender, receiver) = channel();
+        client.request("window/workDoneProgress/create", WorkDoneProgressCreateParams { token: token.clone() }, move |result| {
+            let _ = sender.send(result);
+        });
+        match receiver.recv_timeout(CREATE_TIMEOUT) {
+            Ok(Ok(_)) => progress.token = Some(token),
+            Ok(Err(err)) => warn!("cannot create a progress: {}", err.message),
+            Err(_) => warn!("cannot create a progress: no answer from the client"),
+        }
+        progress.send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: title.to_string(),
+            cancellable: Some(false),
+            message: None,
+            percentage: Some(0),
+        }));
+        progress
+    }
+
+    pub fn report(&self, message: String, percentage: u32) {
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: None,
+            message: Some(message),
+            percentage: Some(percentage.min(100)),
+        }));
+    }
+
+    pub fn end(self, message: String) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd { message: Some(message) }));
+    }
+
+    fn send(&self, value: WorkDoneProgress) {
+        if let Some(token) = &self.token {
+            self.client.notify("$/progress", ProgressParams { token: token.clone(), value });
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Project file, `leek.toml`, checked in at the root of a workspace folder.
+//!
+//! ```toml
+//! [project]
+//! language-version = 4
+//! entry-points = ["main.leek"]
+//! include-paths = ["lib"]
+//!
+//! [lints]
+//! unused-variable = "off"
+//!
+//! [formatter]
+//! indent-style = "spaces"
+//! indent-width = 2
+//! ```
+//!
+//! Settings declared by the project file take precedence over the editor
+//! settings, so that everybody working on the project gets the same
+//! diagnostics and formatting.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::settings::{BraceStyle, IndentStyle, LintLevel, Settings, LATEST_VERSION};
+
+pub const FILE_NAME: &str = "leek.toml";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all="kebab-case", deny_unknown_fields)]
+pub struct ProjectFile {
+    #[serde(default)]
+    pub project: ProjectSection,
+    #[serde(default)]
+    pub lints: HashMap<String, LintLevel>,
+    #[serde(default)]
+    pub formatter: FormatterSection,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all="kebab-case", deny_unknown_fields)]
+pub struct ProjectSection {
+    pub name: Option<String>,
+    pub language_version: Option<u8>,
+    /// AI files run by the game, relative to the project root.
+    #[serde(default)]
+    pub entry_points: Vec<String>,
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+    pub ops_budget: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all="kebab-case", deny_unknown_fields)]
+pub struct FormatterSection {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_width: Option<u32>,
+    pub max_line_length: Option<u32>,
+    pub brace_style: Option<BraceStyle>,
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProjectError::Io(err) => write!(f, "cannot read {}: {}", FILE_NAME, err),
+            ProjectError::Parse(err) => write!(f, "invalid {}: {}", FILE_NAME, err),
+            ProjectError::Invalid(message) => write!(f, "invalid {}: {}", FILE_NAME, message),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl ProjectFile {
+    pub fn parse(text: &str) -> Result<ProjectFile, ProjectError> {
+        let file: ProjectFile = toml::from_str(text).map_err(ProjectError::Parse)?;
+        if let Some(version) = file.project.language_version {
+            if !(1..=LATEST_VERSION).contains(&version) {
+                return Err(ProjectError::Invalid(format!(
+                    "language version {} is not between 1 and {}",
+                    version, LATEST_VERSION
+                )));
+            }
+        }
+        Ok(file)
+    }
+}
+
+/// A project: a workspace folder and its project file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Project {
+    pub root: PathBuf,
+    pub file: ProjectFile,
+}
+
+impl Project {
+    /// Load the project file of the folder `root`. Returns `Ok(None)` when
+    /// the folder has no project file.
+    pub fn load(root: &Path) -> Result<Option<Project>, ProjectError> {
+        let text = match fs::read_to_string(root.join(FILE_NAME)) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(ProjectError::Io(err)),
+        };
+        Ok(Some(Project { root: root.to_path_buf(), file: ProjectFile::parse(&text)? }))
+    }
+
+    pub fn entry_points(&self) -> Vec<PathBuf> {
+        self.file.project.entry_points.iter().map(|path| self.root.join(path)).collect()
+    }
+
+    pub fn include_paths(&self) -> Vec<PathBuf> {
+        self.file.project.include_paths.iter().map(|path| self.root.join(path)).collect()
+    }
+
+    /// Override `settings` with the ones declared by the project file.
+    pub fn apply(&self, settings: &mut Settings) {
+        let project = &self.file.project;
+        if let Some(version) = project.language_version {
+            settings.language_version = version;
+        }
+        if !project.include_paths.is_empty() {
+            settings.include_paths = project.include_paths.clone();
+        }
+        if project.ops_budget.is_some() {
+            settings.ops_budget = project.ops_budget;
+        }
+        settings.lints.extend(self.file.lints.iter().map(|(rule, level)| (rule.clone(), *level)));
+
+        let formatter = &self.file.formatter;
+        if let Some(indent_style) = formatter.indent_style {
+            settings.formatter.indent_style = indent_style;
+        }
+        if let Some(indent_width) = formatter.indent_width {
+            settings.formatter.indent_width = indent_width;
+        }
+        if let Some(max_line_length) = formatter.max_line_length {
+            settings.formatter.max_line_length = max_line_length;
+        }
+        if let Some(brace_style) = formatter.brace_style {
+            settings.formatter.brace_style = brace_style;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_project_file() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(Project::load(root.path()).unwrap().is_none());
+
+        fs::write(root.path().join(FILE_NAME), concat!(
+            "[project]\n",
+            "language-version = 2\n",
+            "entry-points = [\"main.leek\"]\n",
+            "include-paths = [\"lib\"]\n",
+            "[lints]\n",
+            "unused-variable = \"error\"\n",
+            "[formatter]\n",
+            "indent-style = \"spaces\"\n",
+            "brace-style = \"next-line\"\n",
+        )).unwrap();
+        let project = Project::load(root.path()).unwrap().unwrap();
+        assert_eq!(project.entry_points(), vec![root.path().join("main.leek")]);
+
+        let mut settings = Settings::default();
+        settings.lints.insert("shadowing".to_string(), LintLevel::Off);
+        project.apply(&mut settings);
+        assert_eq!(settings.language_version, 2);
+        assert_eq!(settings.include_paths, vec!["lib".to_string()]);
+        assert_eq!(settings.lint_level("unused-variable", LintLevel::Warning), LintLevel::Error);
+        assert_eq!(settings.lint_level("shadowing", LintLevel::Warning), LintLevel::Off);
+        assert_eq!(settings.formatter.indent_style, IndentStyle::Spaces);
+        assert_eq!(settings.formatter.brace_style, BraceStyle::NextLine);
+        assert_eq!(settings.formatter.indent_width, 4);
+    }
+
+    #[test]
+    fn invalid_project_files() {
+        assert!(matches!(ProjectFile::parse("[project]\nlanguage-version = 9"), Err(ProjectError::Invalid(_))));
+        assert!(matches!(ProjectFile::parse("[project]\nentry-point = \"main.leek\""), Err(ProjectError::Parse(_))));
+        assert_eq!(ProjectFile::parse("").unwrap(), ProjectFile::default());
+    }
+}
@@ -0,0 +1,499 @@
+//! Scheduling of incoming messages.
+//!
+//! Notifications and requests changing the state of the server are handled
+//! one at a time, in the order they arrive. Requests only reading the state
+//! run concurrently on a pool of workers, so that a slow request does not
+//! hold back the edits typed in the meantime. Such a request works on a
+//! [`Snapshot`] of the state taken when it arrives, before the messages
+//! following it are handled: it never sees the edits which arrived since.
+//!
+//! A running request is cancelled by `$/cancelRequest`, or when the
+//! document it is about changes; it then answers with `RequestCancelled`
+//! or `ContentModified`. Handlers may call [`check_cancelled`] to give up
+//! early.
+//!
+//! A panicking handler answers with `InternalError`; the panic is logged
+//! and the document the handler was working on is handed to a recovery
+//! function, so that nothing computed by the faulty handler is trusted.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use jsonrpc_core::{
+    to_string, Error, Failure, Id, MetaIoHandler, Middleware, Output, Result as RPCResult, Value, Version,
+};
+use log::{error, warn};
+
+use crate::client::Client;
+use crate::lsp::{errors, CancelParams, DocumentUri, Either};
+use crate::panics;
+use crate::transport;
+
+/// Requests which do not change the state of the server.
+pub const READ_ONLY: &[&str] = &[
+    "textDocument/hover",
+    "textDocument/completion",
+    "completionItem/resolve",
+    "textDocument/signatureHelp",
+    "textDocument/declaration",
+    "textDocument/definition",
+    "textDocument/typeDefinition",
+    "textDocument/implementation",
+    "textDocument/references",
+    "textDocument/documentHighlight",
+    "textDocument/documentSymbol",
+    "textDocument/codeAction",
+    "textDocument/codeLens",
+    "textDocument/documentLink",
+    "textDocument/foldingRange",
+    "textDocument/selectionRange",
+    "textDocument/semanticTokens/full",
+    "textDocument/semanticTokens/full/delta",
+    "textDocument/semanticTokens/range",
+    "textDocument/inlayHint",
+    "textDocument/diagnostic",
+    "textDocument/formatting",
+    "textDocument/rangeFormatting",
+    "textDocument/prepareRename",
+    "textDocument/rename",
+    "workspace/symbol",
+    "workspace/diagnostic",
+];
+
+/// Notifications making the requests running on a document stale.
+const MODIFYING: &[&str] = &["textDocument/didChange", "textDocument/didClose"];
+
+const RUNNING: u8 = 0;
+const CANCELLED: u8 = 1;
+const CONTENT_MODIFIED: u8 = 2;
+
+/// Cancellation state of a running request, shared between the scheduler
+/// and the worker running it.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicU8>);
+
+impl CancellationToken {
+    fn set(&self, state: u8) {
+        // The first reason wins.
+        let _ = self.0.compare_exchange(RUNNING, state, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    pub fn cancel(&self) {
+        self.set(CANCELLED);
+    }
+
+    /// Cancel the request because the document it is about changed.
+    pub fn content_modified(&self) {
+        self.set(CONTENT_MODIFIED);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst) != RUNNING
+    }
+
+    /// The error to answer with if the request was cancelled.
+    pub fn check(&self) -> RPCResult<()> {
+        match self.0.load(Ordering::SeqCst) {
+            CANCELLED => Err(errors::request_cancelled()),
+            CONTENT_MODIFIED => Err(errors::content_modified()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// State of the server captured for a read-only request when it arrives.
+pub type Snapshot = Arc<dyn Any + Send + Sync>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+    static SNAPSHOT: RefCell<Option<Snapshot>> = const { RefCell::new(None) };
+}
+
+/// Fail with the appropriate error if the request being handled on this
+/// thread was cancelled. Always succeeds outside of a scheduled request.
+pub fn check_cancelled() -> RPCResult<()> {
+    CURRENT.with(|current| current.borrow().as_ref().map_or(Ok(()), CancellationToken::check))
+}
+
+/// The snapshot the request being handled on this thread works on, if it
+/// was taken as a `T`.
+pub fn snapshot<T: Any + Send + Sync>() -> Option<Arc<T>> {
+    SNAPSHOT.with(|snapshot| snapshot.borrow().clone()).and_then(|snapshot| snapshot.downcast().ok())
+}
+
+/// Run `f` with `snapshot` as the state seen by [`snapshot`].
+pub fn with_snapshot<T>(snapshot: Snapshot, f: impl FnOnce() -> T) -> T {
+    let previous = SNAPSHOT.with(|current| current.replace(Some(snapshot)));
+    let result = f();
+    SNAPSHOT.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+struct Running {
+    token: CancellationToken,
+    /// Document the request is about, if any.
+    uri: Option<DocumentUri>,
+}
+
+struct Job {
+    id: Id,
+    method: String,
+    uri: Option<DocumentUri>,
+    message: String,
+    token: CancellationToken,
+    snapshot: Snapshot,
+}
+
+type Recover = dyn Fn(&str) + Send + Sync;
+
+/// Run the handler `f` of `method`. A panic is logged and turned into an
+/// `InternalError`, after calling `recover` on the document `uri`.
+fn isolate<T>(method: &str, uri: Option<&str>, recover: &Recover, f: impl FnOnce() -> T) -> RPCResult<T> {
+    panics::catch(f).map_err(|panic| {
+        error!("Handler of '{}' panicked: {}", method, panic);
+        if let Some(uri) = uri {
+            if let Err(panic) = panics::catch(|| recover(uri)) {
+                error!("Recovery of {} panicked: {}", uri, panic);
+            }
+        }
+        errors::internal_error(&panic.message)
+    })
+}
+
+fn failure(id: Id, error: Error) -> Option<String> {
+    to_string(&Output::Failure(Failure { jsonrpc: Some(Version::V2), error, id })).ok()
+}
+
+/// Dispatcher of the incoming messages of a connection.
+pub struct Scheduler {
+    client: Client,
+    running: Arc<Mutex<HashMap<Id, Running>>>,
+    dispatch: Box<dyn Fn(&str) + Send + Sync>,
+    recover: Arc<Recover>,
+    snapshot: Box<dyn Fn() -> Snapshot + Send + Sync>,
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Scheduler {
+    /// Schedule the messages handled by `io`, running read-only requests
+    /// on `workers` threads on the state returned by `snapshot`. `recover`
+    /// is called with the URI of the document a handler was working on when
+    /// it panicked.
+    pub fn new<S, F, G>(io: MetaIoHandler<(), S>, client: Client, workers: usize, recover: F, snapshot: G) -> Self
+    where
+        S: Middleware<()> + Send + Sync + 'static,
+        F: Fn(&str) + Send + Sync + 'static,
+        G: Fn() -> Snapshot + Send + Sync + 'static,
+    {
+        let recover: Arc<Recover> = Arc::new(recover);
+        let io = Arc::new(io);
+        let running = Arc::new(Mutex::new(HashMap::new()));
+        let (jobs, receiver) = channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..workers.max(1))
+            .filter_map(|i| {
+                let (io, client, running) = (io.clone(), client.clone(), running.clone());
+                let (receiver, recover) = (receiver.clone(), recover.clone());
+                let spawned = thread::Builder::new()
+                    .name(format!("worker-{}", i))
+                    .spawn(move || work(&io, &client, &running, &receiver, &*recover));
+                spawned.map_err(|err| warn!("cannot start a worker: {}", err)).ok()
+            })
+            .collect();
+        let dispatch = {
+            let client = client.clone();
+            Box::new(move |message: &str| transport::dispatch(&io, &client, message))
+        };
+        let snapshot = Box::new(snapshot);
+        Scheduler { client, running, dispatch, recover, snapshot, jobs: Some(jobs), workers }
+    }
+
+    /// Number of workers to use on this machine.
+    pub fn default_workers() -> usize {
+        thread::available_parallelism().map_or(2, |n| n.get().min(4))
+    }
+
+    /// Handle one incoming message.
+    pub fn dispatch(&self, message: &str) {
+        let call = match serde_json::from_str::<Value>(message) {
+            Ok(Value::Object(call)) => call,
+            // Let the JSON-RPC handler answer invalid messages.
+            _ => return (self.dispatch)(message),
+        };
+        let method = call.get("method").and_then(Value::as_str).unwrap_or_default();
+        let id = call.get("id").cloned().and_then(|id| serde_json::from_value::<Id>(id).ok());
+        let uri = call
+            .get("params")
+            .and_then(|params| params.pointer("/textDocument/uri"))
+            .and_then(Value::as_str);
+
+        if method == "$/cancelRequest" {
+            transport::log_incoming(&call);
+            let params = call.get("params").cloned().map(serde_json::from_value::<CancelParams>);
+            if let Some(Ok(params)) = params {
+                let id = match params.id {
+                    Either::Left(id) => u64::try_from(id).map(Id::Num).ok(),
+                    Either::Right(id) => Some(Id::Str(id)),
+                };
+                if let Some(id) = id {
+                    self.cancel(&id);
+                }
+            }
+            return;
+        }
+        if MODIFYING.contains(&method) {
+            if let Some(uri) = uri {
+                self.content_modified(uri);
+            }
+        }
+        match (id, &self.jobs) {
+            (Some(id), Some(jobs)) if READ_ONLY.contains(&method) => {
+                transport::log_incoming(&call);
+                let token = CancellationToken::default();
+                let running = Running { token: token.clone(), uri: uri.map(str::to_string) };
+                self.running.lock().unwrap().insert(id.clone(), running);
+                let (method, uri) = (method.to_string(), uri.map(str::to_string));
+                let snapshot = (self.snapshot)();
+                let _ = jobs.send(Job { id, method, uri, message: message.to_string(), token, snapshot });
+            }
+            (id, _) => {
+                if let Err(error) = isolate(method, uri, &*self.recover, || (self.dispatch)(message)) {
+                    if let Some(response) = id.and_then(|id| failure(id, error)) {
+                        self.client.send(response);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cancel the request `id`, if it is still running.
+    pub fn cancel(&self, id: &Id) {
+        if let Some(running) = self.running.lock().unwrap().get(id) {
+            running.token.cancel();
+        }
+    }
+
+    /// Cancel the requests about the document `uri`.
+    fn content_modified(&self, uri: &str) {
+        for running in self.running.lock().unwrap().values() {
+            if running.uri.as_deref() == Some(uri) {
+                running.token.content_modified();
+            }
+        }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl Drop for Scheduler {
+    /// Wait for the running requests.
+    fn drop(&mut self) {
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn work<S: Middleware<()>>(
+    io: &MetaIoHandler<(), S>,
+    client: &Client,
+    running: &Mutex<HashMap<Id, Running>>,
+    jobs: &Mutex<Receiver<Job>>,
+    recover: &Recover,
+) {
+    loop {
+        // Only hold the lock while waiting, not while working.
+        let job = match jobs.lock().unwrap().recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        let response = match job.token.check() {
+            Ok(()) => {
+                CURRENT.with(|current| *current.borrow_mut() = Some(job.token.clone()));
+                let response = with_snapshot(job.snapshot.clone(), || {
+                    isolate(&job.method, job.uri.as_deref(), recover, || io.handle_request_sync(&job.message, ()))
+                });
+                CURRENT.with(|current| current.borrow_mut().take());
+                response
+            }
+            Err(_) => Ok(None),
+        };
+        running.lock().unwrap().remove(&job.id);
+        // Whatever the handler found, a cancelled request answers with the
+        // reason of the cancellation.
+        let response = match (job.token.check(), response) {
+            (Ok(()), Ok(response)) => response,
+            (Err(error), _) | (_, Err(error)) => failure(job.id, error),
+        };
+        if let Some(response) = response {
+            client.send(response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifecycle::Lifecycle;
+    use crate::transport::{self, Outgoing};
+    use jsonrpc_core::Params;
+    use serde_json::json;
+    use std::sync::mpsc::Sender;
+    use std::time::Duration;
+
+    /// A scheduler whose `textDocument/hover` and `textDocument/documentSymbol`
+    /// handlers wait for a go from the test, and whose `textDocument/didChange`
+    /// handler reports when it runs. The snapshots are the number of changes.
+    fn scheduler() -> (Scheduler, Receiver<Outgoing>, Sender<()>, Receiver<()>, Receiver<String>) {
+        let (recovered, recoveries) = channel();
+        let (go, wait) = channel::<()>();
+        let wait = Arc::new(Mutex::new(wait));
+        let (changed, changes) = channel();
+        let changed = Mutex::new(changed);
+        let edits = Arc::new(AtomicU8::new(0));
+        let lifecycle = Lifecycle::default();
+        let mut io = MetaIoHandler::with_middleware(lifecycle);
+        io.add_method("initialize", |_| Ok(Value::Null));
+        io.add_notification("initialized", |_| {});
+        let waiting = wait.clone();
+        io.add_method("textDocument/hover", move |_: Params| {
+            waiting.lock().unwrap().recv_timeout(Duration::from_secs(5)).unwrap();
+            check_cancelled()?;
+            Ok(json!("hover"))
+        });
+        io.add_method("textDocument/documentSymbol", move |_: Params| {
+            wait.lock().unwrap().recv_timeout(Duration::from_secs(5)).unwrap();
+            Ok(json!(*snapshot::<u8>().unwrap()))
+        });
+        let changing = edits.clone();
+        io.add_notification("textDocument/didChange", move |_| {
+            changing.fetch_add(1, Ordering::SeqCst);
+            changed.lock().unwrap().send(()).unwrap()
+        });
+        io.add_method("textDocument/definition", |_| -> RPCResult<Value> { panic!("bug in definition") });
+        io.add_method("workspace/executeCommand", |_| -> RPCResult<Value> { panic!("bug in command") });
+
+        let (sender, outgoing) = transport::outgoing();
+        let recovered = Mutex::new(recovered);
+        let scheduler = Scheduler::new(
+            io,
+            Client::new(sender),
+            2,
+            move |uri| recovered.lock().unwrap().send(uri.to_string()).unwrap(),
+            move || Arc::new(edits.load(Ordering::SeqCst)),
+        );
+        scheduler.dispatch(&json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }).to_string());
+        scheduler.dispatch(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }).to_string());
+        outgoing.recv().unwrap().into_message().unwrap();
+        (scheduler, outgoing, go, changes, recoveries)
+    }
+
+    fn hover(id: u64, uri: &str) -> String {
+        json!({
+            "jsonrpc": "2.0", "id": id, "method": "textDocument/hover",
+            "params": { "textDocument": { "uri": uri }, "position": { "line": 0, "character": 0 } },
+        })
+        .to_string()
+    }
+
+    fn receive(outgoing: &Receiver<Outgoing>) -> Value {
+        serde_json::from_str(&outgoing.recv_timeout(Duration::from_secs(5)).unwrap().into_message().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn notifications_are_not_held_back() {
+        let (scheduler, outgoing, go, changes, _) = scheduler();
+        scheduler.dispatch(&hover(1, "file:///a.leek"));
+        scheduler.dispatch(&json!({
+            "jsonrpc": "2.0", "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": "file:///b.leek", "version": 2 }, "contentChanges": [] },
+        }).to_string());
+        // The notification is handled while the hover is still waiting.
+        changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        go.send(()).unwrap();
+        assert_eq!(receive(&outgoing), json!({ "jsonrpc": "2.0", "id": 1, "result": "hover" }));
+    }
+
+    #[test]
+    fn cancel_requests() {
+        let (scheduler, outgoing, go, _, _) = scheduler();
+        scheduler.dispatch(&hover(1, "file:///a.leek"));
+        scheduler.dispatch(&json!({ "jsonrpc": "2.0", "method": "$/cancelRequest", "params": { "id": 1 } }).to_string());
+        go.send(()).unwrap();
+        let response = receive(&outgoing);
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["error"]["code"], errors::REQUEST_CANCELLED);
+
+        // Cancelling a finished request does nothing.
+        scheduler.dispatch(&json!({ "jsonrpc": "2.0", "method": "$/cancelRequest", "params": { "id": 1 } }).to_string());
+        assert!(outgoing.try_recv().is_err());
+    }
+
+    #[test]
+    fn edits_make_requests_stale() {
+        let (scheduler, outgoing, go, changes, _) = scheduler();
+        scheduler.dispatch(&hover(1, "file:///a.leek"));
+        scheduler.dispatch(&hover(2, "file:///b.leek"));
+        scheduler.dispatch(&json!({
+            "jsonrpc": "2.0", "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": "file:///a.leek", "version": 2 }, "contentChanges": [] },
+        }).to_string());
+        changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        go.send(()).unwrap();
+        go.send(()).unwrap();
+
+        let mut responses = [receive(&outgoing), receive(&outgoing)];
+        responses.sort_by_key(|response| response["id"].as_u64());
+        assert_eq!(responses[0]["error"]["code"], errors::CONTENT_MODIFIED);
+        assert_eq!(responses[1]["result"], "hover");
+    }
+
+    #[test]
+    fn requests_work_on_a_snapshot() {
+        let (scheduler, outgoing, go, changes, _) = scheduler();
+        scheduler.dispatch(&json!({
+            "jsonrpc": "2.0", "id": 1, "method": "textDocument/documentSymbol",
+            "params": { "textDocument": { "uri": "file:///a.leek" } },
+        }).to_string());
+        scheduler.dispatch(&json!({
+            "jsonrpc": "2.0", "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": "file:///b.leek", "version": 2 }, "contentChanges": [] },
+        }).to_string());
+        changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        go.send(()).unwrap();
+        // The request still sees the state from before the change.
+        assert_eq!(receive(&outgoing), json!({ "jsonrpc": "2.0", "id": 1, "result": 0 }));
+    }
+
+    #[test]
+    fn isolate_panics() {
+        let (scheduler, outgoing, _go, _, recoveries) = scheduler();
+        let definition = json!({
+            "jsonrpc": "2.0", "id": 1, "method": "textDocument/definition",
+            "params": { "textDocument": { "uri": "file:///a.leek" }, "position": { "line": 0, "character": 0 } },
+        });
+        scheduler.dispatch(&definition.to_string());
+        let response = receive(&outgoing);
+        assert_eq!(response["error"]["code"], -32603);
+        assert!(response["error"]["message"].as_str().unwrap().contains("bug in definition"));
+        assert_eq!(recoveries.recv_timeout(Duration::from_secs(5)).unwrap(), "file:///a.leek");
+
+        // Requests handled in order are isolated too.
+        let command = json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/executeCommand", "params": {} });
+        scheduler.dispatch(&command.to_string());
+        assert_eq!(receive(&outgoing)["error"]["code"], -32603);
+        assert!(recoveries.try_recv().is_err());
+        scheduler.dispatch(&definition.to_string());
+        assert_eq!(receive(&outgoing)["error"]["code"], -32603);
+    }
+}
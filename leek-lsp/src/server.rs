@@ -0,0 +1,1077 @@
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use jsonrpc_core::{MetaIoHandler, Params, Result as RPCResult};
+use log::{error, info, warn};
+use serde_json::Value;
+
+use crate::analysis::AnalysisCache;
+use crate::checks::{self, CheckResults};
+use crate::client::Client;
+use crate::debounce::Debouncer;
+use crate::documents::DocumentStore;
+use crate::index::{self, SymbolIndex};
+use crate::lifecycle::Lifecycle;
+use crate::logging;
+use crate::lsp::*;
+use crate::negotiation::Negotiated;
+use crate::panics;
+use crate::progress::Progress;
+use crate::project::{self, Project};
+use crate::scheduler::{self, Scheduler};
+use crate::settings::{self, Configuration, Settings};
+use crate::uri;
+use crate::watcher::{self, FileWatcher};
+use crate::workspace::Workspace;
+
+/// How long to wait after the last change of a document before publishing
+/// its diagnostics.
+const DIAGNOSTICS_DELAY: Duration = Duration::from_millis(200);
+
+/// State shared by every handler of the server.
+#[derive(Default, Debug)]
+pub struct ServerState {
+    /// Capabilities announced by the client in `initialize`.
+    pub client_capabilities: RwLock<ClientCapabilities>,
+    /// What responses may use, derived from `client_capabilities`.
+    pub negotiated: RwLock<Negotiated>,
+    /// Workspace folders and their projects.
+    pub workspace: RwLock<Workspace>,
+    pub configuration: RwLock<Configuration>,
+    /// Settings read from the `--config` file, overridden by the ones of
+    /// the client.
+    pub default_settings: RwLock<Value>,
+    pub documents: DocumentStore,
+    pub analyses: AnalysisCache,
+    /// Results of the whole-project checks of the open documents.
+    pub checks: CheckResults,
+    /// Delays the diagnostics of the documents being edited.
+    pub debouncer: OnceLock<Debouncer>,
+    /// Symbols of the files of the workspace and of the open documents.
+    pub index: SymbolIndex,
+    /// Our own file watcher, when the client cannot watch files for us.
+    pub watcher: Mutex<Option<FileWatcher>>,
+}
+
+impl ServerState {
+    /// A copy of the documents, their analyses and the index, as they are
+    /// now.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            documents: self.documents.snapshot(),
+            analyses: self.analyses.snapshot(),
+            index: self.index.snapshot(),
+        }
+    }
+
+    /// Make the state usable again after a handler panicked while holding
+    /// one of its locks.
+    pub fn clear_poison(&self) {
+        self.client_capabilities.clear_poison();
+        self.negotiated.clear_poison();
+        self.workspace.clear_poison();
+        self.configuration.clear_poison();
+        self.default_settings.clear_poison();
+        self.watcher.clear_poison();
+        self.documents.clear_poison();
+        self.analyses.clear_poison();
+        self.checks.clear_poison();
+        self.index.clear_poison();
+        if let Some(debouncer) = self.debouncer.get() {
+            debouncer.clear_poison();
+        }
+    }
+}
+
+/// The part of the state read-only requests work on, taken when they arrive
+/// so that the edits handled meanwhile do not change it under them.
+#[derive(Debug)]
+pub struct Snapshot {
+    pub documents: DocumentStore,
+    pub analyses: AnalysisCache,
+    pub index: SymbolIndex,
+}
+
+/// The LeekScript language server.
+///
+/// Cloning the server is cheap: clones share the same state and talk to
+/// the same client.
+#[derive(Clone)]
+pub struct LeekLSP {
+    state: Arc<ServerState>,
+    client: Client,
+    lifecycle: Lifecycle,
+}
+
+impl LeekLSP {
+    pub fn new(client: Client) -> Self {
+        let server = LeekLSP {
+            state: Arc::new(ServerState::default()),
+            client,
+            lifecycle: Lifecycle::default(),
+        };
+        // The debouncer belongs to the state, it must not keep it alive.
+        let state = Arc::downgrade(&server.state);
+        let (client, lifecycle) = (server.client.clone(), server.lifecycle.clone());
+        let debouncer = Debouncer::new(DIAGNOSTICS_DELAY, move |uri| {
+            if let Some(state) = state.upgrade() {
+                let server = LeekLSP { state, client: client.clone(), lifecycle: lifecycle.clone() };
+                server.publish_debounced(&uri);
+            }
+        });
+        let _ = server.state.debouncer.set(debouncer);
+        server
+    }
+
+    pub fn state(&self) -> &ServerState {
+        &self.state
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn lifecycle(&self) -> &Lifecycle {
+        &self.lifecycle
+    }
+
+    /// Build the JSON-RPC handler serving this server.
+    pub fn io_handler(&self) -> MetaIoHandler<(), Lifecycle> {
+        let mut io = MetaIoHandler::with_middleware(self.lifecycle.clone());
+        io.extend_with(self.clone().to_delegate());
+        io
+    }
+
+    /// Build the scheduler dispatching the messages of the client to this
+    /// server, on `workers` threads.
+    pub fn scheduler(&self, workers: usize) -> Scheduler {
+        let (server, state) = (self.clone(), self.state.clone());
+        Scheduler::new(
+            self.io_handler(),
+            self.client.clone(),
+            workers,
+            move |uri| server.recover(uri),
+            move || Arc::new(state.snapshot()),
+        )
+    }
+
+    /// The state the current request works on: the snapshot taken when it
+    /// arrived, or the state as it is now outside of the scheduler.
+    fn snapshot(&self) -> Arc<Snapshot> {
+        scheduler::snapshot().unwrap_or_else(|| Arc::new(self.state.snapshot()))
+    }
+
+    /// Called after a handler panicked while working on the document
+    /// `uri`: the state it may have left half updated is analysed again
+    /// from scratch.
+    pub fn recover(&self, uri: &str) {
+        self.state.clear_poison();
+        self.state.analyses.invalidate(uri);
+    }
+
+    /// Features negotiated with the client in `initialize`.
+    pub fn negotiated(&self) -> Negotiated {
+        self.state.negotiated.read().unwrap().clone()
+    }
+
+    /// Capabilities announced in the response to `initialize`.
+    pub fn server_capabilities(&self) -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(Either::Left(TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::Incremental),
+                save: Some(Either::Right(SaveOptions { include_text: Some(false) })),
+                ..Default::default()
+            })),
+            workspace_symbol_provider: Some(Either::Left(true)),
+            workspace: Some(WorkspaceServerCapabilities {
+                workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                    supported: Some(true),
+                    change_notifications: Some(Either::Left(true)),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Use `defaults` for the settings the client does not set.
+    pub fn set_default_settings(&self, defaults: Value) {
+        *self.state.default_settings.write().unwrap() = defaults.clone();
+        self.state.configuration.write().unwrap().global = Settings::from_value(defaults);
+    }
+
+    /// Read settings sent by the client, on top of the default ones.
+    fn read_settings(&self, value: Value) -> Settings {
+        let mut settings = self.state.default_settings.read().unwrap().clone();
+        settings::merge(&mut settings, value);
+        Settings::from_value(settings)
+    }
+
+    /// Settings applying to the document `uri`: the editor settings,
+    /// overridden by the project file of its workspace folder.
+    pub fn settings(&self, uri: &str) -> Settings {
+        let mut settings = self.state.configuration.read().unwrap().for_document(uri).clone();
+        if let Some(project) = self.project(uri) {
+            project.apply(&mut settings);
+        }
+        settings
+    }
+
+    /// Project of the innermost workspace folder containing `uri`.
+    pub fn project(&self, uri: &str) -> Option<Project> {
+        let workspace = self.state.workspace.read().unwrap();
+        workspace.root_of(uri).and_then(|root| root.project.clone())
+    }
+
+    /// (Re)load the project file of the workspace folder `folder`.
+    fn load_project(&self, folder: &str) {
+        let root = match uri::to_path(folder) {
+            Some(root) => root,
+            None => return,
+        };
+        let mut workspace = self.state.workspace.write().unwrap();
+        let folder = match workspace.get_mut(folder) {
+            Some(folder) => folder,
+            None => return,
+        };
+        match Project::load(&root) {
+            Ok(Some(project)) => {
+                info!("Loaded {}", root.join(project::FILE_NAME).display());
+                folder.project = Some(project);
+            }
+            Ok(None) => folder.project = None,
+            Err(err) => {
+                // Keep the last valid project file while the user fixes it.
+                warn!("{}: {}", root.display(), err);
+                self.client.notify("window/showMessage", ShowMessageParams {
+                    typ: MessageType::Warning,
+                    message: format!("{}: {}", root.display(), err),
+                });
+            }
+        }
+    }
+
+    /// Ask the client for the settings of each workspace folder, if it
+    /// supports `workspace/configuration`.
+    fn pull_configuration(&self) {
+        let capabilities = self.state.client_capabilities.read().unwrap().clone();
+        if !capabilities.workspace.and_then(|workspace| workspace.configuration).unwrap_or(false) {
+            return;
+        }
+        let folders = self.state.workspace.read().unwrap().folders();
+        let mut items = vec![ConfigurationItem { scope_uri: None, section: Some(settings::SECTION.to_string()) }];
+        items.extend(folders.iter().map(|folder| ConfigurationItem {
+            scope_uri: Some(folder.uri.clone()),
+            section: Some(settings::SECTION.to_string()),
+        }));
+
+        let server = self.clone();
+        self.client.request("workspace/configuration", ConfigurationParams { items }, move |result| {
+            let values = match result.map(serde_json::from_value::<Vec<Value>>) {
+                Ok(Ok(values)) => values,
+                Ok(Err(err)) => return warn!("invalid workspace/configuration response: {}", err),
+                Err(err) => return warn!("workspace/configuration failed: {}", err.message),
+            };
+            // Clients answer `null` for what they have no settings for: the
+            // global settings are then kept, and folders use them.
+            let mut values = values.into_iter();
+            let mut configuration = server.state.configuration.write().unwrap();
+            let global = match values.next() {
+                Some(value) if !value.is_null() => server.read_settings(value),
+                _ => configuration.global.clone(),
+            };
+            *configuration = Configuration { global, ..Default::default() };
+            for (folder, value) in folders.into_iter().zip(values).filter(|(_, value)| !value.is_null()) {
+                configuration.folders.insert(folder.uri, server.read_settings(value));
+            }
+            drop(configuration);
+            // Responses are handled on the thread reading the messages,
+            // which the checks would hold back.
+            server.refresh_diagnostics_in_background();
+        });
+    }
+
+    /// Register for configuration changes when the client only sends them
+    /// to servers asking for it.
+    fn register_configuration(&self) {
+        let capabilities = self.state.client_capabilities.read().unwrap().clone();
+        let dynamic = capabilities
+            .workspace
+            .and_then(|workspace| workspace.did_change_configuration)
+            .and_then(|capabilities| capabilities.dynamic_registration)
+            .unwrap_or(false);
+        if dynamic {
+            let registration = Registration {
+                id: "workspace/didChangeConfiguration".to_string(),
+                method: "workspace/didChangeConfiguration".to_string(),
+                register_options: Some(serde_json::json!({ "section": settings::SECTION })),
+            };
+            self.client.request("client/registerCapability", RegistrationParams { registrations: vec![registration] }, |result| {
+                if let Err(err) = result {
+                    warn!("cannot register for configuration changes: {}", err.message);
+                }
+            });
+        }
+    }
+
+    /// Index the files of `folders` on a background thread, reporting
+    /// progress to the client.
+    fn index_folders(&self, folders: Vec<WorkspaceFolder>) {
+        if folders.is_empty() {
+            return;
+        }
+        let server = self.clone();
+        let spawned = thread::Builder::new()
+            .name("indexer".to_string())
+            .spawn(move || server.index(&folders));
+        if let Err(err) = spawned {
+            warn!("cannot start indexing: {}", err);
+        }
+    }
+
+    fn index(&self, folders: &[WorkspaceFolder]) {
+        let progress = Progress::begin(&self.client, self.negotiated().work_done_progress, "Indexing");
+        let files: Vec<_> = folders
+            .iter()
+            .filter_map(|folder| uri::to_path(&folder.uri))
+            .flat_map(|root| index::leek_files(&root))
+            .collect();
+        let mut percentage = 0;
+        for (i, path) in files.iter().enumerate() {
+            if let Some(uri) = uri::from_path(path) {
+                // Open documents are indexed from their content, and the
+                // folder may have been removed in the meantime.
+                let open = self.state.documents.version(&uri).is_some();
+                if !open && self.state.workspace.read().unwrap().root_of(&uri).is_some() {
+                    self.state.index.update_from_disk(path);
+                }
+            }
+            let done = ((i + 1) * 100 / files.len()) as u32;
+            if done > percentage {
+                percentage = done;
+                progress.report(format!("{}/{} files", i + 1, files.len()), percentage);
+            }
+        }
+        info!("Indexed {} files", files.len());
+        progress.end(format!("{} files indexed", files.len()));
+        // Now that the whole project is known.
+        self.check_open_documents();
+    }
+
+    /// Index the current content of the open document `uri`.
+    fn update_index(&self, uri: &str) {
+        if let Some(document) = self.state.documents.get(uri) {
+            self.state.index.update(&document);
+        }
+    }
+
+    /// Ask the client to watch the LeekScript and project files, or watch
+    /// them ourselves if it cannot.
+    fn watch_files(&self) {
+        let capabilities = self.state.client_capabilities.read().unwrap().clone();
+        let dynamic = capabilities
+            .workspace
+            .and_then(|workspace| workspace.did_change_watched_files)
+            .and_then(|capabilities| capabilities.dynamic_registration)
+            .unwrap_or(false);
+        if !dynamic {
+            return self.start_watcher();
+        }
+        let watchers = watcher::PATTERNS
+            .iter()
+            .map(|pattern| FileSystemWatcher { glob_pattern: Either::Left(pattern.to_string()), kind: None })
+            .collect();
+        let registration = Registration {
+            id: "workspace/didChangeWatchedFiles".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions { watchers }).ok(),
+        };
+        let server = self.clone();
+        self.client.request("client/registerCapability", RegistrationParams { registrations: vec![registration] }, move |result| {
+            if let Err(err) = result {
+                warn!("cannot register file watchers, watching files ourselves: {}", err.message);
+                server.start_watcher();
+            }
+        });
+    }
+
+    fn start_watcher(&self) {
+        let server = self.clone();
+        let mut watcher = match FileWatcher::new(move |changes| server.files_changed(&changes)) {
+            Ok(watcher) => watcher,
+            Err(err) => return warn!("cannot watch files: {}", err),
+        };
+        for root in self.state.workspace.read().unwrap().roots() {
+            if let Some(path) = root.path() {
+                watcher.watch(&path);
+            }
+        }
+        info!("Watching workspace files");
+        *self.state.watcher.lock().unwrap() = Some(watcher);
+    }
+
+    /// Handle changes of files on disk, reported by the client or by our
+    /// own watcher.
+    fn files_changed(&self, changes: &[FileEvent]) {
+        let workspace = self.state.workspace.read().unwrap().clone();
+        let mut changed = false;
+        for change in changes {
+            // Only the project file at the root of a folder counts.
+            if let Some(root) = change.uri.strip_suffix(project::FILE_NAME) {
+                if let Some(root) = workspace.get(root) {
+                    self.load_project(root.uri());
+                    changed = true;
+                }
+                continue;
+            }
+            // The content of open documents is the one of the editor.
+            let indexed = workspace.root_of(&change.uri).is_some() && self.state.documents.version(&change.uri).is_none();
+            if !indexed || !change.uri.ends_with(&format!(".{}", index::EXTENSION)) {
+                continue;
+            }
+            match (change.typ, uri::to_path(&change.uri)) {
+                (FileChangeType::Deleted, _) | (_, None) => self.state.index.remove(&change.uri),
+                (_, Some(path)) => {
+                    self.state.index.update_from_disk(&path);
+                }
+            }
+            changed = true;
+        }
+        if changed {
+            self.refresh_diagnostics();
+        }
+    }
+
+    /// Analyse the open documents again, after a change of the settings or
+    /// of the files they may depend on.
+    fn refresh_diagnostics(&self) {
+        self.state.analyses.clear();
+        self.check_open_documents();
+    }
+
+    /// [`refresh_diagnostics`](Self::refresh_diagnostics) on a thread of
+    /// its own.
+    fn refresh_diagnostics_in_background(&self) {
+        let server = self.clone();
+        let spawned = thread::Builder::new()
+            .name("diagnostics".to_string())
+            .spawn(move || {
+                if let Err(panic) = panics::catch(|| server.refresh_diagnostics()) {
+                    error!("Refreshing the diagnostics panicked: {}", panic);
+                }
+            });
+        if let Err(err) = spawned {
+            warn!("cannot refresh the diagnostics: {}", err);
+        }
+    }
+
+    /// Run the whole-project checks on the open document `uri`.
+    fn check(&self, uri: &str) {
+        if let Some(document) = self.state.documents.get(uri) {
+            let settings = self.settings(uri);
+            let workspace = self.state.workspace.read().unwrap().clone();
+            let settings_of = |uri: &str| self.settings(uri);
+            let project = checks::Project {
+                index: &self.state.index,
+                workspace: &workspace,
+                settings: &settings,
+                settings_of: &settings_of,
+            };
+            let diagnostics = checks::check(&document, &project);
+            // When checking in the background, the document may have been
+            // edited meanwhile: its previous results follow the edits.
+            if self.state.documents.version(uri) == Some(document.version) {
+                self.state.checks.set(uri, diagnostics);
+            }
+        }
+    }
+
+    /// Check every open document and publish their diagnostics.
+    fn check_open_documents(&self) {
+        for uri in self.state.documents.uris() {
+            self.check(&uri);
+            self.publish_diagnostics(&uri);
+        }
+    }
+
+    /// Publish the syntax diagnostics of the current version of `uri`,
+    /// with the results of its last checks.
+    fn publish_diagnostics(&self, uri: &str) {
+        if let Some(document) = self.state.documents.get(uri) {
+            let analysis = self.state.analyses.get(&document);
+            let mut diagnostics = analysis.diagnostics.clone();
+            diagnostics.extend(self.state.checks.get(uri).iter().cloned());
+            let diagnostics = self.negotiated().diagnostics(diagnostics);
+            self.client.publish_diagnostics(document.uri, Some(analysis.version), diagnostics);
+        }
+    }
+
+    /// Publish the diagnostics of `uri` once its edits calmed down.
+    fn publish_debounced(&self, uri: &str) {
+        if let Err(panic) = panics::catch(|| self.publish_diagnostics(uri)) {
+            error!("Diagnostics of {} panicked: {}", uri, panic);
+            self.recover(uri);
+        }
+    }
+}
+
+impl LSP for LeekLSP {
+    fn initialize(&self, params: Params) -> RPCResult<InitializeResult> {
+        let params: InitializeParams = params.parse()?;
+        logging::set_trace(params.trace.unwrap_or_default());
+
+        let folders = match (params.workspace_folders, params.root_uri) {
+            (Some(folders), _) => folders,
+            (None, Some(uri)) => {
+                let name = uri::file_name(&uri).to_string();
+                vec![WorkspaceFolder { uri, name }]
+            }
+            (None, None) => Vec::new(),
+        };
+        for folder in folders {
+            let uri = folder.uri.clone();
+            self.state.workspace.write().unwrap().add(folder);
+            self.load_project(&uri);
+        }
+        *self.state.negotiated.write().unwrap() = Negotiated::new(&params.capabilities);
+        *self.state.client_capabilities.write().unwrap() = params.capabilities;
+        let global = self.read_settings(params.initialization_options.unwrap_or_default());
+        self.state.configuration.write().unwrap().global = global;
+
+        Ok(InitializeResult {
+            capabilities: self.server_capabilities(),
+            server_info: Some(ServerInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    fn initialized(&self, _params: Params) {
+        info!("Client initialized");
+        self.register_configuration();
+        self.pull_configuration();
+        self.watch_files();
+        let folders = self.state.workspace.read().unwrap().folders();
+        self.index_folders(folders);
+    }
+
+    fn shutdown(&self) -> RPCResult<()> {
+        info!("Shutdown requested");
+        self.state.watcher.lock().unwrap().take();
+        Ok(())
+    }
+
+    fn exit(&self) {
+        let code = self.lifecycle.exit_code();
+        info!("Exit requested, exiting with code {}", code);
+        log::logger().flush();
+        std::process::exit(code)
+    }
+
+    fn set_trace(&self, params: Params) {
+        if let Ok(params) = params.parse::<SetTraceParams>() {
+            logging::set_trace(params.value);
+        }
+    }
+
+    fn did_change_configuration(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidChangeConfigurationParams>() {
+            if let Some(section) = settings::section(params.settings) {
+                self.state.configuration.write().unwrap().global = self.read_settings(section);
+                self.refresh_diagnostics();
+            }
+            self.pull_configuration();
+        }
+    }
+
+    fn did_change_workspace_folders(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidChangeWorkspaceFoldersParams>() {
+            if let Some(watcher) = self.state.watcher.lock().unwrap().as_mut() {
+                for path in params.event.removed.iter().filter_map(|folder| uri::to_path(&folder.uri)) {
+                    watcher.unwatch(&path);
+                }
+                for path in params.event.added.iter().filter_map(|folder| uri::to_path(&folder.uri)) {
+                    watcher.watch(&path);
+                }
+            }
+            for folder in params.event.removed {
+                info!("Removed workspace folder {}", folder.uri);
+                // The client may spell the folder with or without its
+                // trailing slash.
+                if let Some(root) = self.state.workspace.write().unwrap().remove(&folder.uri) {
+                    self.state.configuration.write().unwrap().folders.remove(root.uri());
+                }
+            }
+            let workspace = self.state.workspace.read().unwrap().clone();
+            let documents = &self.state.documents;
+            self.state.index.retain(|uri| workspace.root_of(uri).is_some() || documents.version(uri).is_some());
+
+            let mut added = Vec::new();
+            for folder in params.event.added {
+                info!("Added workspace folder {}", folder.uri);
+                if self.state.workspace.write().unwrap().add(folder.clone()) {
+                    self.load_project(&folder.uri);
+                    added.push(folder);
+                }
+            }
+            self.index_folders(added);
+            // Open documents may have moved to another root.
+            self.refresh_diagnostics();
+            self.pull_configuration();
+        }
+    }
+
+    fn did_change_watched_files(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidChangeWatchedFilesParams>() {
+            self.files_changed(&params.changes);
+        }
+    }
+
+    fn workspace_symbol(&self, params: Params) -> RPCResult<Vec<SymbolInformation>> {
+        let params: WorkspaceSymbolParams = params.parse()?;
+        let symbols = self.snapshot().index.search(&params.query);
+        scheduler::check_cancelled()?;
+        Ok(symbols)
+    }
+
+    fn did_open(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidOpenTextDocumentParams>() {
+            let uri = params.text_document.uri.clone();
+            self.state.documents.open(params.text_document);
+            self.update_index(&uri);
+            self.check(&uri);
+            self.publish_diagnostics(&uri);
+        }
+    }
+
+    fn did_change(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidChangeTextDocumentParams>() {
+            let document = params.text_document;
+            let uri = &document.uri;
+            if self.state.documents.change(uri, document.version, &params.content_changes) {
+                self.state.checks.edit(uri, &params.content_changes);
+                self.update_index(uri);
+                // Only the syntax is checked while typing, once the user
+                // pauses.
+                match self.state.debouncer.get() {
+                    Some(debouncer) => debouncer.schedule(uri),
+                    None => self.publish_diagnostics(uri),
+                }
+            }
+        }
+    }
+
+    fn did_save(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidSaveTextDocumentParams>() {
+            let uri = &params.text_document.uri;
+            if self.state.documents.save(uri, params.text.as_deref()) {
+                if params.text.is_some() {
+                    // The content may differ from ours without a new version.
+                    self.state.analyses.invalidate(uri);
+                    self.update_index(uri);
+                }
+                if let Some(debouncer) = self.state.debouncer.get() {
+                    debouncer.cancel(uri);
+                }
+                // The saved file may change the results of every open
+                // document.
+                self.check_open_documents();
+            }
+        }
+    }
+
+    fn did_close(&self, params: Params) {
+        if let Ok(params) = params.parse::<DidCloseTextDocumentParams>() {
+            let uri = params.text_document.uri;
+            if let Some(debouncer) = self.state.debouncer.get() {
+                debouncer.cancel(&uri);
+            }
+            self.state.analyses.invalidate(&uri);
+            self.state.checks.remove(&uri);
+            if self.state.documents.close(&uri).is_some() {
+                // Back to the content on disk, if the file is part of the
+                // workspace.
+                let path = uri::to_path(&uri).filter(|_| self.state.workspace.read().unwrap().root_of(&uri).is_some());
+                if !path.is_some_and(|path| self.state.index.update_from_disk(&path)) {
+                    self.state.index.remove(&uri);
+                }
+                self.client.publish_diagnostics(uri, None, Vec::new());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport;
+    use serde_json::{json, Value};
+
+    fn send(io: &MetaIoHandler<(), Lifecycle>, message: Value) -> Option<Value> {
+        io.handle_request_sync(&message.to_string(), ())
+            .map(|response| serde_json::from_str(&response).unwrap())
+    }
+
+    #[test]
+    fn handlers_share_state() {
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+
+        let response = send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {
+                "processId": null,
+                "rootUri": "file:///home/leek/ais",
+                "capabilities": { "workspace": { "applyEdit": true } },
+            },
+        }));
+        assert_eq!(response.unwrap()["result"]["capabilities"]["textDocumentSync"]["change"], 2);
+        assert_eq!(server.state().workspace.read().unwrap().roots()[0].folder.name, "ais");
+        let capabilities = server.state().client_capabilities.read().unwrap().clone();
+        assert_eq!(capabilities.workspace.unwrap().apply_edit, Some(true));
+
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": { "textDocument": {
+                "uri": "file:///home/leek/ais/main.leek", "languageId": "leekscript",
+                "version": 1, "text": "var a = ;",
+            } },
+        }));
+        assert!(server.state().documents.get("file:///home/leek/ais/main.leek").is_some());
+        let notification: Value = serde_json::from_str(&outgoing.try_recv().unwrap().into_message().unwrap()).unwrap();
+        assert_eq!(notification["method"], "textDocument/publishDiagnostics");
+        assert_eq!(notification["params"]["diagnostics"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn check_results_follow_edits() {
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+        send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "processId": null, "rootUri": "file:///ais", "capabilities": {} },
+        }));
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": { "textDocument": {
+                "uri": "file:///ais/main.leek", "languageId": "leekscript", "version": 1,
+                "text": "debug(life);\ndebug(range);\n",
+            } },
+        }));
+        let receive = || -> Value {
+            let message = outgoing.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+            serde_json::from_str(&message.into_message().unwrap()).unwrap()
+        };
+        let diagnostics = receive()["params"]["diagnostics"].clone();
+        assert_eq!(diagnostics.as_array().unwrap().len(), 2);
+        assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 0, "character": 6 }));
+
+        // A line inserted before `life`, and `range` renamed. Only the
+        // syntax is checked again, once the edits calm down.
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": "file:///ais/main.leek", "version": 2 },
+                "contentChanges": [
+                    {
+                        "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } },
+                        "text": "// é\n",
+                    },
+                    {
+                        "range": { "start": { "line": 2, "character": 6 }, "end": { "line": 2, "character": 11 } },
+                        "text": "reach",
+                    },
+                ],
+            },
+        }));
+        let notification = receive();
+        assert_eq!(notification["params"]["version"], 2);
+        let diagnostics = notification["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["message"], "`life` is not defined");
+        assert_eq!(diagnostics[0]["range"], json!({
+            "start": { "line": 1, "character": 6 },
+            "end": { "line": 1, "character": 10 },
+        }));
+    }
+
+    #[test]
+    fn pull_configuration_per_folder() {
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+
+        send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {
+                "processId": null,
+                "rootUri": null,
+                "initializationOptions": { "languageVersion": 2 },
+                "capabilities": { "workspace": { "configuration": true } },
+                "workspaceFolders": [
+                    { "uri": "file:///ais", "name": "ais" },
+                    { "uri": "file:///legacy", "name": "legacy" },
+                ],
+            },
+        }));
+        assert_eq!(server.settings("file:///ais/main.leek").language_version, 2);
+
+        send(&io, json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+        let request: Value = serde_json::from_str(&outgoing.try_recv().unwrap().into_message().unwrap()).unwrap();
+        assert_eq!(request["method"], "workspace/configuration");
+        assert_eq!(request["params"]["items"][2], json!({ "scopeUri": "file:///legacy", "section": "leekscript" }));
+
+        let response = json!({
+            "jsonrpc": "2.0", "id": request["id"],
+            "result": [{ "languageVersion": 3 }, null, { "languageVersion": 1 }],
+        });
+        transport::dispatch(&io, server.client(), &response.to_string());
+        assert_eq!(server.settings("file:///ais/main.leek").language_version, 3);
+        assert_eq!(server.settings("file:///legacy/main.leek").language_version, 1);
+
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "workspace/didChangeWorkspaceFolders",
+            "params": { "event": { "added": [], "removed": [{ "uri": "file:///legacy/", "name": "legacy" }] } },
+        }));
+        assert_eq!(server.settings("file:///legacy/main.leek").language_version, 3);
+        let request: Value = serde_json::from_str(&outgoing.try_recv().unwrap().into_message().unwrap()).unwrap();
+        assert_eq!(request["params"]["items"].as_array().unwrap().len(), 2);
+
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "workspace/didChangeConfiguration",
+            "params": { "settings": { "leekscript": { "languageVersion": 1 } } },
+        }));
+        assert_eq!(server.settings("file:///other.leek").language_version, 1);
+        let request: Value = serde_json::from_str(&outgoing.try_recv().unwrap().into_message().unwrap()).unwrap();
+        assert_eq!(request["method"], "workspace/configuration");
+
+        // Without global settings, those of the notification are kept.
+        let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": [null, null, null] });
+        transport::dispatch(&io, server.client(), &response.to_string());
+        assert_eq!(server.settings("file:///other.leek").language_version, 1);
+        assert_eq!(server.settings("file:///legacy/main.leek").language_version, 1);
+    }
+
+    #[test]
+    fn reload_project_file() {
+        let root = tempfile::tempdir().unwrap();
+        let project_file = root.path().join(project::FILE_NAME);
+        std::fs::write(&project_file, "[project]\nlanguage-version = 2\n").unwrap();
+        let root_uri = uri::from_path(root.path()).unwrap();
+        let document = format!("{}/main.leek", root_uri);
+
+        let (sender, _outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+        send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {
+                "processId": null,
+                "rootUri": root_uri,
+                "initializationOptions": { "languageVersion": 3, "lints": { "shadowing": "off" } },
+                "capabilities": {},
+            },
+        }));
+        let settings = server.settings(&document);
+        assert_eq!(settings.language_version, 2);
+        assert_eq!(settings.lints.len(), 1);
+
+        std::fs::write(&project_file, "[lints]\nshadowing = \"error\"\n").unwrap();
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "workspace/didChangeWatchedFiles",
+            "params": { "changes": [{ "uri": uri::from_path(&project_file).unwrap(), "type": 2 }] },
+        }));
+        let settings = server.settings(&document);
+        assert_eq!(settings.language_version, 3);
+        assert_eq!(settings.lints["shadowing"], settings::LintLevel::Error);
+    }
+
+    #[test]
+    fn workspace_folders_are_isolated() {
+        let directory = tempfile::tempdir().unwrap();
+        let (first, second) = (directory.path().join("first"), directory.path().join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(first.join(project::FILE_NAME), "[project]\nlanguage-version = 1\n").unwrap();
+        std::fs::write(second.join(project::FILE_NAME), "[project]\nlanguage-version = 2\n").unwrap();
+        let (first, second) = (uri::from_path(&first).unwrap(), uri::from_path(&second).unwrap());
+
+        let (sender, _outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+        let response = send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {
+                "processId": null,
+                "rootUri": null,
+                "capabilities": {},
+                "workspaceFolders": [{ "uri": first, "name": "first" }],
+            },
+        }));
+        let capabilities = &response.unwrap()["result"]["capabilities"];
+        assert_eq!(capabilities["workspace"]["workspaceFolders"]["changeNotifications"], true);
+        assert_eq!(server.settings(&format!("{}/main.leek", first)).language_version, 1);
+        assert_eq!(server.settings(&format!("{}/main.leek", second)).language_version, settings::LATEST_VERSION);
+
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "workspace/didChangeWorkspaceFolders",
+            "params": { "event": {
+                "added": [{ "uri": second, "name": "second" }],
+                "removed": [{ "uri": first, "name": "first" }],
+            } },
+        }));
+        assert_eq!(server.settings(&format!("{}/main.leek", first)).language_version, settings::LATEST_VERSION);
+        assert_eq!(server.settings(&format!("{}/main.leek", second)).language_version, 2);
+        assert_eq!(server.state().workspace.read().unwrap().roots().len(), 1);
+    }
+
+    #[test]
+    fn index_workspace_in_background() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("lib")).unwrap();
+        std::fs::write(root.path().join("main.leek"), "function attack() { return 1; }").unwrap();
+        std::fs::write(root.path().join("lib/util.leek"), "var life = 1;").unwrap();
+        let root_uri = uri::from_path(root.path()).unwrap();
+
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+        send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {
+                "processId": null,
+                "rootUri": root_uri,
+                "capabilities": { "window": { "workDoneProgress": true } },
+            },
+        }));
+        send(&io, json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+
+        let receive = || -> Value {
+            let message = outgoing.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+            serde_json::from_str(&message.into_message().unwrap()).unwrap()
+        };
+        let create = receive();
+        assert_eq!(create["method"], "window/workDoneProgress/create");
+        let response = json!({ "jsonrpc": "2.0", "id": create["id"], "result": null });
+        transport::dispatch(&io, server.client(), &response.to_string());
+
+        let mut kinds = Vec::new();
+        while kinds.last() != Some(&"end".to_string()) {
+            let progress = receive();
+            assert_eq!(progress["method"], "$/progress");
+            assert_eq!(progress["params"]["token"], create["params"]["token"]);
+            kinds.push(progress["params"]["value"]["kind"].as_str().unwrap().to_string());
+        }
+        assert_eq!(kinds, vec!["begin", "report", "report", "end"]);
+
+        let response = send(&io, json!({
+            "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol", "params": { "query": "" },
+        }));
+        let symbols = response.unwrap()["result"].clone();
+        assert_eq!(symbols[0]["name"], "attack");
+        assert_eq!(symbols[1]["name"], "life");
+        assert_eq!(symbols[1]["location"]["uri"], format!("{}/lib/util.leek", root_uri));
+    }
+
+    #[test]
+    fn watch_workspace_files() {
+        let root = tempfile::tempdir().unwrap();
+        let root_uri = uri::from_path(root.path()).unwrap();
+        let path = root.path().join("util.leek");
+        let file_uri = uri::from_path(&path).unwrap();
+
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+        send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": {
+                "processId": null,
+                "rootUri": root_uri,
+                "capabilities": { "workspace": { "didChangeWatchedFiles": { "dynamicRegistration": true } } },
+            },
+        }));
+        send(&io, json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+        let request: Value = serde_json::from_str(&outgoing.try_recv().unwrap().into_message().unwrap()).unwrap();
+        assert_eq!(request["method"], "client/registerCapability");
+        let registration = &request["params"]["registrations"][0];
+        assert_eq!(registration["method"], "workspace/didChangeWatchedFiles");
+        assert_eq!(registration["registerOptions"]["watchers"][0], json!({ "globPattern": "**/*.leek" }));
+
+        std::fs::write(&path, "function heal() { return 1; }").unwrap();
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "workspace/didChangeWatchedFiles",
+            "params": { "changes": [{ "uri": file_uri, "type": 1 }] },
+        }));
+        assert_eq!(server.state().index.search("heal").len(), 1);
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "workspace/didChangeWatchedFiles",
+            "params": { "changes": [{ "uri": file_uri, "type": 3 }] },
+        }));
+        assert!(server.state().index.search("heal").is_empty());
+
+        // Watch the files ourselves when the client refuses.
+        assert!(server.state().watcher.lock().unwrap().is_none());
+        let response = json!({
+            "jsonrpc": "2.0", "id": request["id"],
+            "error": { "code": -32601, "message": "Method not found" },
+        });
+        transport::dispatch(&io, server.client(), &response.to_string());
+        assert!(server.state().watcher.lock().unwrap().is_some());
+        send(&io, json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }));
+        assert!(server.state().watcher.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn requests_do_not_see_later_edits() {
+        let (sender, _outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let io = server.io_handler();
+        send(&io, json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "processId": null, "rootUri": "file:///ais", "capabilities": {} },
+        }));
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": { "textDocument": {
+                "uri": "file:///ais/main.leek", "languageId": "leekscript", "version": 1,
+                "text": "function heal() {}\n",
+            } },
+        }));
+        // A search arrives, then the document is edited before it runs.
+        let snapshot = server.state().snapshot();
+        send(&io, json!({
+            "jsonrpc": "2.0", "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": "file:///ais/main.leek", "version": 2 },
+                "contentChanges": [{ "text": "function attack() {}\n" }],
+            },
+        }));
+        let search = json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol", "params": { "query": "heal" } });
+        let response = scheduler::with_snapshot(Arc::new(snapshot), || send(&io, search.clone())).unwrap();
+        assert_eq!(response["result"][0]["name"], "heal");
+        assert_eq!(send(&io, search).unwrap()["result"], json!([]));
+    }
+
+    #[test]
+    fn recover_from_panics() {
+        let (sender, _outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let state = server.state.clone();
+        let _ = thread::spawn(move || {
+            let _workspace = state.workspace.write().unwrap();
+            panic!("bug while holding the workspace");
+        })
+        .join();
+        assert!(server.state().workspace.is_poisoned());
+
+        server.recover("file:///ai.leek");
+        assert!(!server.state().workspace.is_poisoned());
+        assert_eq!(server.settings("file:///ai.leek"), Settings::default());
+    }
+}
@@ -0,0 +1,248 @@
+//! User settings of the server.
+//!
+//! Settings are read from the `leekscript` section of the client
+//! configuration. They are first received in `initializationOptions`, then
+//! pulled per workspace folder with `workspace/configuration` when the
+//! client supports it, and refreshed on `workspace/didChangeConfiguration`.
+//! Settings given by the client override the ones of the file passed with
+//! `--config`.
+
+use std::collections::HashMap;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::lsp::DiagnosticSeverity;
+use crate::uri;
+
+/// Name of the configuration section holding our settings.
+pub const SECTION: &str = "leekscript";
+
+/// Latest version of the LeekScript language.
+pub const LATEST_VERSION: u8 = 4;
+
+/// Whether `version` is a version of the LeekScript language.
+pub const fn is_valid_version(version: u8) -> bool {
+    version >= 1 && version <= LATEST_VERSION
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase", default)]
+pub struct Settings {
+    /// Version of LeekScript the AIs are written in, from 1 to 4.
+    pub language_version: u8,
+    /// Level of each lint rule, by rule name; rules not listed keep their
+    /// default level.
+    pub lints: HashMap<String, LintLevel>,
+    pub formatter: FormatterSettings,
+    /// Directories searched by `include`, relative to the workspace folder.
+    pub include_paths: Vec<String>,
+    /// Maximum number of operations an AI may use in a turn, warned about
+    /// by the operations estimation.
+    pub ops_budget: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            language_version: LATEST_VERSION,
+            lints: HashMap::new(),
+            formatter: FormatterSettings::default(),
+            include_paths: Vec::new(),
+            ops_budget: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum LintLevel {
+    Off,
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LintLevel {
+    /// Severity of the diagnostics reported at this level, `None` when the
+    /// rule is disabled.
+    pub fn severity(self) -> Option<DiagnosticSeverity> {
+        match self {
+            LintLevel::Off => None,
+            LintLevel::Hint => Some(DiagnosticSeverity::Hint),
+            LintLevel::Info => Some(DiagnosticSeverity::Information),
+            LintLevel::Warning => Some(DiagnosticSeverity::Warning),
+            LintLevel::Error => Some(DiagnosticSeverity::Error),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all="lowercase")]
+pub enum IndentStyle {
+    Tabs,
+    Spaces,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase")]
+pub enum BraceStyle {
+    /// `if (x) {`
+    #[serde(alias="same-line")]
+    SameLine,
+    /// The opening brace on its own line.
+    #[serde(alias="next-line")]
+    NextLine,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all="camelCase", default)]
+pub struct FormatterSettings {
+    pub indent_style: IndentStyle,
+    pub indent_width: u32,
+    pub max_line_length: u32,
+    pub brace_style: BraceStyle,
+}
+
+impl Default for FormatterSettings {
+    fn default() -> Self {
+        FormatterSettings {
+            indent_style: IndentStyle::Tabs,
+            indent_width: 4,
+            max_line_length: 120,
+            brace_style: BraceStyle::SameLine,
+        }
+    }
+}
+
+impl Settings {
+    /// Read settings sent by the client. Missing settings take their
+    /// default value; invalid settings are reported and replaced by the
+    /// defaults.
+    pub fn from_value(value: Value) -> Settings {
+        if value.is_null() {
+            return Settings::default();
+        }
+        let mut settings: Settings = match serde_json::from_value(value) {
+            Ok(settings) => settings,
+            Err(err) => {
+                warn!("invalid settings, using the defaults: {}", err);
+                return Settings::default();
+            }
+        };
+        if !is_valid_version(settings.language_version) {
+            warn!(
+                "invalid language version {}, expected 1 to {}, using {}",
+                settings.language_version, LATEST_VERSION, LATEST_VERSION
+            );
+            settings.language_version = LATEST_VERSION;
+        }
+        settings
+    }
+
+    /// Level of the lint rule `rule`, or `default` if not configured.
+    pub fn lint_level(&self, rule: &str, default: LintLevel) -> LintLevel {
+        self.lints.get(rule).copied().unwrap_or(default)
+    }
+}
+
+/// Our section of the client configuration `value`.
+pub fn section(mut value: Value) -> Option<Value> {
+    value.get_mut(SECTION).map(Value::take)
+}
+
+/// Override the settings `base` with `overrides`, key by key.
+pub fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value),
+                    None if !value.is_null() => {
+                        base.insert(key, value);
+                    }
+                    None => {}
+                }
+            }
+        }
+        (_, Value::Null) => {}
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Settings in effect: the global ones and the ones of each workspace
+/// folder.
+#[derive(Clone, Debug, Default)]
+pub struct Configuration {
+    pub global: Settings,
+    /// Settings by workspace folder URI.
+    pub folders: HashMap<String, Settings>,
+}
+
+impl Configuration {
+    /// Settings applying to the document `uri`: those of the innermost
+    /// workspace folder containing it, or the global ones.
+    pub fn for_document(&self, uri: &str) -> &Settings {
+        self.folders
+            .iter()
+            .filter(|(folder, _)| uri::contains(folder, uri))
+            .max_by_key(|(folder, _)| folder.len())
+            .map(|(_, settings)| settings)
+            .unwrap_or(&self.global)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_settings() {
+        let settings = Settings::from_value(json!({
+            "languageVersion": 3,
+            "lints": { "unused-variable": "off", "undefined-function": "error" },
+            "formatter": { "indentStyle": "spaces", "braceStyle": "nextLine" },
+            "includePaths": ["lib"],
+            "opsBudget": 20000000,
+        }));
+        assert_eq!(settings.language_version, 3);
+        assert_eq!(settings.lint_level("unused-variable", LintLevel::Warning).severity(), None);
+        assert_eq!(settings.lint_level("shadowing", LintLevel::Hint), LintLevel::Hint);
+        assert_eq!(settings.formatter.indent_style, IndentStyle::Spaces);
+        assert_eq!(settings.formatter.indent_width, 4);
+        assert_eq!(settings.ops_budget, Some(20_000_000));
+
+        assert_eq!(Settings::from_value(json!({ "languageVersion": "four" })), Settings::default());
+        let settings = Settings::from_value(json!({ "languageVersion": 9, "includePaths": ["lib"] }));
+        assert_eq!(settings.language_version, LATEST_VERSION);
+        assert_eq!(settings.include_paths, ["lib"]);
+        assert_eq!(Settings::from_value(json!({ "languageVersion": 0 })).language_version, LATEST_VERSION);
+        assert_eq!(section(json!({ "editor": {} })), None);
+        assert_eq!(section(json!({ "leekscript": { "languageVersion": 1 } })), Some(json!({ "languageVersion": 1 })));
+    }
+
+    #[test]
+    fn merge_settings() {
+        let mut settings = json!({ "languageVersion": 2, "lints": { "shadowing": "off" }, "includePaths": ["lib"] });
+        merge(&mut settings, json!({ "lints": { "unused-variable": "error" }, "includePaths": [], "opsBudget": null }));
+        assert_eq!(settings, json!({
+            "languageVersion": 2,
+            "lints": { "shadowing": "off", "unused-variable": "error" },
+            "includePaths": [],
+        }));
+    }
+
+    #[test]
+    fn settings_by_folder() {
+        let mut configuration = Configuration::default();
+        let version = |version| Settings { language_version: version, ..Settings::default() };
+        configuration.folders.insert("file:///ais".to_string(), version(2));
+        configuration.folders.insert("file:///ais/lib/".to_string(), version(3));
+        assert_eq!(configuration.for_document("file:///ais/main.leek").language_version, 2);
+        assert_eq!(configuration.for_document("file:///ais/lib/util.leek").language_version, 3);
+        assert_eq!(configuration.for_document("file:///aisle.leek").language_version, LATEST_VERSION);
+    }
+}
@@ -0,0 +1,322 @@
+//! Implementation of the LSP base protocol: every message is a JSON-RPC
+//! payload preceded by a `Content-Length` header.
+//!
+//! ```text
+//! Content-Length: 52\r\n
+//! \r\n
+//! {"jsonrpc":"2.0","id":1,"method":"shutdown"}
+//! ```
+//!
+//! Messages are exchanged on stdin/stdout, on a TCP connection or, for
+//! clients running in a browser, on a [`websocket`].
+
+use std::io::{self, BufRead, BufReader, Error, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use jsonrpc_core::{MetaIoHandler, Middleware, Output, Value};
+use log::{error, info, warn};
+use serde_json::Map;
+
+use crate::client::Client;
+use crate::logging;
+use crate::scheduler::Scheduler;
+
+pub mod websocket;
+
+/// How the server talks to its client. The server handles a single
+/// connection and stops when it is closed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Stdio,
+    /// TCP on the local host: connect to the client listening on `port`,
+    /// or with `listen`, wait for the client to connect to `port`.
+    Tcp { port: u16, listen: bool },
+    /// Wait for a WebSocket client on the given address.
+    WebSocket(SocketAddr),
+}
+
+impl Transport {
+    /// Serve the messages dispatched by `scheduler` until the client goes
+    /// away.
+    pub fn serve(&self, scheduler: Scheduler, outgoing: Receiver<Outgoing>) -> io::Result<()> {
+        match *self {
+            Transport::Stdio => {
+                serve_stdio(scheduler, outgoing);
+                Ok(())
+            }
+            Transport::Tcp { port, listen: false } => {
+                let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+                serve_tcp(scheduler, outgoing, stream)
+            }
+            Transport::Tcp { port, listen: true } => {
+                let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+                info!("Waiting for a client on {}", listener.local_addr()?);
+                let (stream, peer) = listener.accept()?;
+                info!("Client connected from {}", peer);
+                serve_tcp(scheduler, outgoing, stream)
+            }
+            Transport::WebSocket(address) => {
+                let listener = TcpListener::bind(address)?;
+                info!("Waiting for a WebSocket client on {}", listener.local_addr()?);
+                websocket::serve_listener(scheduler, outgoing, listener)
+            }
+        }
+    }
+}
+
+/// What the server queues for the transport to write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outgoing {
+    Message(String),
+    /// The connection is closed: stop once the messages queued before are
+    /// written.
+    Close,
+}
+
+impl Outgoing {
+    /// The queued message, `None` for [`Outgoing::Close`].
+    pub fn into_message(self) -> Option<String> {
+        match self {
+            Outgoing::Message(message) => Some(message),
+            Outgoing::Close => None,
+        }
+    }
+}
+
+/// Create the queue of outgoing messages shared by the server and the
+/// transport.
+pub fn outgoing() -> (Sender<Outgoing>, Receiver<Outgoing>) {
+    channel()
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Read the next message from `reader`.
+///
+/// Returns `Ok(None)` when the stream is closed between two messages. A
+/// malformed header or body yields an `InvalidData` error; the stream is
+/// positioned after the faulty message and reading can go on.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut content_length = None;
+    let mut malformed = None;
+    let mut line = String::new();
+    let mut first = true;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return if first {
+                Ok(None)
+            } else {
+                Err(Error::new(ErrorKind::UnexpectedEof, "stream closed inside a message header"))
+            };
+        }
+        let header = line.trim_end_matches(&['\r', '\n'][..]);
+        if header.is_empty() {
+            if first {
+                // Tolerate blank lines between messages.
+                continue;
+            }
+            break;
+        }
+        first = false;
+        let (name, value) = match header.split_once(':') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => {
+                malformed = Some(format!("malformed header `{}`", header));
+                continue;
+            }
+        };
+        if name.eq_ignore_ascii_case("Content-Length") {
+            match value.parse::<usize>() {
+                Ok(length) => content_length = Some(length),
+                Err(_) => malformed = Some(format!("invalid Content-Length `{}`", value)),
+            }
+        } else if name.eq_ignore_ascii_case("Content-Type") {
+            let charset = value.split(';').map(str::trim).find_map(|param| param.strip_prefix("charset="));
+            if let Some(charset) = charset {
+                // `utf8` is accepted for backwards compatibility.
+                if !charset.eq_ignore_ascii_case("utf-8") && !charset.eq_ignore_ascii_case("utf8") {
+                    malformed = Some(format!("unsupported charset `{}`", charset));
+                }
+            }
+        }
+    }
+
+    let length = match content_length {
+        Some(length) => length,
+        None => return Err(invalid_data(malformed.unwrap_or_else(|| "missing Content-Length header".to_string()))),
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    if let Some(message) = malformed {
+        return Err(invalid_data(message));
+    }
+    String::from_utf8(body).map(Some).map_err(|_| invalid_data("message is not valid UTF-8".to_string()))
+}
+
+/// Write `message` to `writer` with its header.
+pub fn write_message<W: Write>(writer: &mut W, message: &str) -> io::Result<()> {
+    write!(writer, "Content-Length: {}\r\n\r\n{}", message.len(), message)?;
+    writer.flush()
+}
+
+/// Handle one incoming message: requests and notifications go to `io`,
+/// responses to our own requests go to `client`.
+pub fn dispatch<S: Middleware<()>>(io: &MetaIoHandler<(), S>, client: &Client, message: &str) {
+    if let Ok(output) = serde_json::from_str::<Output>(message) {
+        client.handle_response(output);
+        return;
+    }
+    if let Ok(Value::Object(call)) = serde_json::from_str::<Value>(message) {
+        log_incoming(&call);
+    }
+    if let Some(response) = io.handle_request_sync(message, ()) {
+        client.send(response);
+    }
+}
+
+/// Trace an incoming request or notification.
+pub fn log_incoming(call: &Map<String, Value>) {
+    if let Some(Value::String(method)) = call.get("method") {
+        let trace = match call.get("id") {
+            Some(id) => format!("Received request '{} - ({})'.", method, id),
+            None => format!("Received notification '{}'.", method),
+        };
+        logging::log_trace(&trace, || {
+            format!("Params: {}", call.get("params").unwrap_or(&Value::Null))
+        });
+    }
+}
+
+/// Serve the messages dispatched by `scheduler` over a pair of streams
+/// until `input` is closed.
+///
+/// Responses and messages queued on `outgoing` are written by a dedicated
+/// thread, so notifications sent by handlers are never interleaved with
+/// responses.
+pub fn serve<R, W>(scheduler: Scheduler, outgoing: Receiver<Outgoing>, input: R, mut output: W)
+where
+    R: Read,
+    W: Write + Send + 'static,
+{
+    let writer = thread::spawn(move || {
+        for message in outgoing.iter().map_while(Outgoing::into_message) {
+            if write_message(&mut output, &message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let client = scheduler.client().clone();
+    let mut input = BufReader::new(input);
+    loop {
+        match read_message(&mut input) {
+            Ok(Some(message)) => scheduler.dispatch(&message),
+            Ok(None) => break,
+            Err(err) if err.kind() == ErrorKind::InvalidData => {
+                warn!("skipping malformed message: {}", err);
+            }
+            Err(err) => {
+                error!("cannot read message: {}", err);
+                break;
+            }
+        }
+    }
+
+    client.cancel_pending();
+    // Let the running requests answer before stopping the writer.
+    drop(scheduler);
+    client.close();
+    let _ = writer.join();
+}
+
+/// Serve on stdin/stdout.
+pub fn serve_stdio(scheduler: Scheduler, outgoing: Receiver<Outgoing>) {
+    serve(scheduler, outgoing, io::stdin(), io::stdout());
+}
+
+/// Serve on a TCP connection, with the same framing as on stdio.
+pub fn serve_tcp(scheduler: Scheduler, outgoing: Receiver<Outgoing>, stream: TcpStream) -> io::Result<()> {
+    // Messages are small and latency matters more than throughput.
+    stream.set_nodelay(true)?;
+    let input = stream.try_clone()?;
+    serve(scheduler, outgoing, input, stream);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A reader returning at most one byte per call.
+    struct Trickle<R>(R);
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    fn framed(body: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    #[test]
+    fn read_partial_messages() {
+        let input = framed("{\"id\":1}") + &framed("{\"id\":\"é\"}");
+        let mut reader = BufReader::with_capacity(3, Trickle(Cursor::new(input)));
+        assert_eq!(read_message(&mut reader).unwrap().as_deref(), Some("{\"id\":1}"));
+        assert_eq!(read_message(&mut reader).unwrap().as_deref(), Some("{\"id\":\"é\"}"));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn recover_from_malformed_headers() {
+        let input = "Content-Length: abc\r\n\r\n".to_string()
+            + "Content-Type: application/vscode-jsonrpc\r\n\r\n"
+            + "Content-Length: 2\r\nContent-Type: application/vscode-jsonrpc; charset=latin1\r\n\r\n{}"
+            + "garbage\r\nContent-Length: 2\r\n\r\n{}"
+            + &framed("{}");
+        let mut reader = Cursor::new(input);
+        for _ in 0..4 {
+            let err = read_message(&mut reader).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+        assert_eq!(read_message(&mut reader).unwrap().as_deref(), Some("{}"));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_message() {
+        let mut reader = Cursor::new("Content-Length: 10\r\n\r\n{}");
+        assert_eq!(read_message(&mut reader).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn write_header() {
+        let mut output = Vec::new();
+        write_message(&mut output, "{\"id\":\"é\"}").unwrap();
+        assert_eq!(output, framed("{\"id\":\"é\"}").into_bytes());
+    }
+
+    #[test]
+    fn route_client_responses() {
+        let (sender, outgoing) = outgoing();
+        let client = Client::new(sender);
+        let (result_sender, result) = channel();
+        client.request("workspace/configuration", (), move |value| result_sender.send(value).unwrap());
+        let request = outgoing.recv().unwrap().into_message().unwrap();
+        let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+        assert_eq!(request["method"], "workspace/configuration");
+
+        let response = format!("{{\"jsonrpc\":\"2.0\",\"id\":{},\"result\":[42]}}", request["id"]);
+        dispatch(&jsonrpc_core::IoHandler::new(), &client, &response);
+        assert_eq!(result.recv().unwrap().unwrap(), serde_json::json!([42]));
+    }
+}
@@ -0,0 +1,106 @@
+//! WebSocket transport. Each text message carries one JSON-RPC message,
+//! without the `Content-Length` header, as expected by browser clients
+//! such as the Monaco language client.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use log::{error, info, warn};
+use tungstenite::protocol::Role;
+use tungstenite::{Error, Message, WebSocket};
+
+use super::Outgoing;
+use crate::scheduler::Scheduler;
+
+/// Serve the first client connecting to `listener`.
+pub fn serve_listener(scheduler: Scheduler, outgoing: Receiver<Outgoing>, listener: TcpListener) -> io::Result<()> {
+    let (stream, peer) = listener.accept()?;
+    info!("WebSocket client connected from {}", peer);
+    serve(scheduler, outgoing, stream)
+}
+
+/// One end of the connection. Each end reads from its own handle on the
+/// stream, but writes under a lock shared with the other end: tungstenite
+/// writes whole frames at once, so the pongs sent by the reading end never
+/// land in the middle of a message sent by the writing end.
+struct Half {
+    stream: TcpStream,
+    output: Arc<Mutex<TcpStream>>,
+}
+
+impl Half {
+    fn new(stream: &TcpStream, output: &Arc<Mutex<TcpStream>>) -> io::Result<Half> {
+        Ok(Half { stream: stream.try_clone()?, output: output.clone() })
+    }
+}
+
+impl Read for Half {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.read(buf)
+    }
+}
+
+impl Write for Half {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.lock().unwrap().write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.lock().unwrap().flush()
+    }
+}
+
+/// Perform the WebSocket handshake on `stream`, then serve the client
+/// until the connection is closed.
+///
+/// Like on the other transports, messages are read on this thread and
+/// written by a dedicated one.
+pub fn serve(scheduler: Scheduler, outgoing: Receiver<Outgoing>, stream: TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let output = Arc::new(Mutex::new(stream.try_clone()?));
+    let handshake = tungstenite::accept(Half::new(&stream, &output)?);
+    let mut socket = handshake.map_err(|err| io::Error::other(err.to_string()))?;
+    let mut sender = WebSocket::from_raw_socket(Half::new(&stream, &output)?, Role::Server, None);
+    let writer = thread::spawn(move || {
+        for message in outgoing.iter().map_while(Outgoing::into_message) {
+            match sender.send(Message::Text(message)) {
+                Ok(()) => {}
+                Err(Error::ConnectionClosed) | Err(Error::AlreadyClosed) => break,
+                Err(err) => {
+                    error!("cannot send message: {}", err);
+                    break;
+                }
+            }
+        }
+    });
+
+    let client = scheduler.client().clone();
+    loop {
+        match socket.read() {
+            Ok(Message::Text(message)) => scheduler.dispatch(&message),
+            Ok(Message::Binary(message)) => match String::from_utf8(message) {
+                Ok(message) => scheduler.dispatch(&message),
+                Err(_) => warn!("skipping message: not valid UTF-8"),
+            },
+            // Pings are answered and closing handshakes completed by
+            // tungstenite itself.
+            Ok(_) => {}
+            Err(Error::ConnectionClosed) | Err(Error::AlreadyClosed) => break,
+            Err(err) => {
+                error!("cannot read message: {}", err);
+                break;
+            }
+        }
+    }
+
+    client.cancel_pending();
+    // Let the running requests answer before stopping the writer.
+    drop(scheduler);
+    client.close();
+    let _ = writer.join();
+    Ok(())
+}
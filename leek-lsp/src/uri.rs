@@ -0,0 +1,43 @@
+//! Conversions between document URIs and file system paths.
+
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+/// Path of a `file://` URI.
+pub fn to_path(uri: &str) -> Option<PathBuf> {
+    Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// `file://` URI of an absolute path.
+pub fn from_path(path: &Path) -> Option<String> {
+    Url::from_file_path(path).ok().map(String::from)
+}
+
+/// Whether `uri` is inside the folder `folder`.
+pub fn contains(folder: &str, uri: &str) -> bool {
+    let folder = folder.trim_end_matches('/');
+    uri.strip_prefix(folder).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Last segment of `uri`, used to name workspace folders.
+pub fn file_name(uri: &str) -> &str {
+    uri.trim_end_matches('/').rsplit('/').next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_and_folders() {
+        let path = to_path("file:///home/leek/my%20ais/main.leek").unwrap();
+        assert_eq!(path, PathBuf::from("/home/leek/my ais/main.leek"));
+        assert_eq!(from_path(&path).unwrap(), "file:///home/leek/my%20ais/main.leek");
+        assert_eq!(to_path("untitled:Untitled-1"), None);
+
+        assert!(contains("file:///ais/", "file:///ais/lib/a.leek"));
+        assert!(!contains("file:///ais", "file:///aisle.leek"));
+        assert_eq!(file_name("file:///home/leek/ais/"), "ais");
+    }
+}
@@ -0,0 +1,157 @@
+//! File watching done by the server itself, for clients unable to send
+//! `workspace/didChangeWatchedFiles`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use log::warn;
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::index::EXTENSION;
+use crate::lsp::{FileChangeType, FileEvent};
+use crate::project;
+use crate::uri;
+
+/// Glob patterns of the files the server is interested in.
+pub const PATTERNS: [&str; 2] = ["**/*.leek", "**/leek.toml"];
+
+/// Events are delivered once no file changed for this long, so that a
+/// `git pull` touching many files is handled at once.
+const QUIET_PERIOD: Duration = Duration::from_millis(100);
+
+/// Whether the server is interested in changes of the file `path`.
+pub fn is_watched(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == EXTENSION)
+        || path.file_name().is_some_and(|name| name == project::FILE_NAME)
+}
+
+/// Recursive watcher of the workspace folders.
+///
+/// Changes are reported in batches to the handler given to
+/// [`FileWatcher::new`], on a thread of its own, in the same form as
+/// `workspace/didChangeWatchedFiles` notifications. The thread stops when
+/// the watcher is dropped.
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+}
+
+impl fmt::Debug for FileWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FileWatcher").finish_non_exhaustive()
+    }
+}
+
+impl FileWatcher {
+    pub fn new<F>(handler: F) -> notify::Result<FileWatcher>
+    where
+        F: Fn(Vec<FileEvent>) + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })?;
+        thread::Builder::new()
+            .name("file-watcher".to_string())
+            .spawn(move || deliver(receiver, handler))?;
+        Ok(FileWatcher { watcher })
+    }
+
+    pub fn watch(&mut self, root: &Path) {
+        if let Err(err) = self.watcher.watch(root, RecursiveMode::Recursive) {
+            warn!("cannot watch {}: {}", root.display(), err);
+        }
+    }
+
+    pub fn unwatch(&mut self, root: &Path) {
+        let _ = self.watcher.unwatch(root);
+    }
+}
+
+fn deliver<F: Fn(Vec<FileEvent>)>(receiver: Receiver<notify::Result<notify::Event>>, handler: F) {
+    let mut changes = HashMap::new();
+    // Wait for a first event, then gather the following ones until things
+    // calm down.
+    while let Ok(event) = receiver.recv() {
+        let mut event = Some(event);
+        loop {
+            match event.take() {
+                Some(Ok(event)) => record(&mut changes, event),
+                Some(Err(err)) => warn!("file watcher error: {}", err),
+                None => {}
+            }
+            match receiver.recv_timeout(QUIET_PERIOD) {
+                Ok(next) => event = Some(next),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        let events: Vec<_> = changes
+            .drain()
+            .filter_map(|(path, typ)| uri::from_path(&path).map(|uri| FileEvent { uri, typ }))
+            .collect();
+        if !events.is_empty() {
+            handler(events);
+        }
+    }
+}
+
+/// Merge `event` into the pending changes, keeping the last known state of
+/// each file.
+fn record(changes: &mut HashMap<PathBuf, FileChangeType>, event: notify::Event) {
+    for path in event.paths {
+        if !is_watched(&path) {
+            continue;
+        }
+        let typ = match event.kind {
+            EventKind::Create(_) => FileChangeType::Created,
+            // Both ends of a rename are reported, tell them apart.
+            EventKind::Modify(ModifyKind::Name(_)) if !path.exists() => FileChangeType::Deleted,
+            EventKind::Modify(ModifyKind::Name(_)) => FileChangeType::Created,
+            EventKind::Modify(_) => FileChangeType::Changed,
+            EventKind::Remove(_) => FileChangeType::Deleted,
+            _ => continue,
+        };
+        let typ = match (changes.get(&path), typ) {
+            // Still a creation as far as the server is concerned.
+            (Some(FileChangeType::Created), FileChangeType::Changed) => FileChangeType::Created,
+            _ => typ,
+        };
+        changes.insert(path, typ);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn watched_files() {
+        assert!(is_watched(Path::new("/ais/main.leek")));
+        assert!(is_watched(Path::new("/ais/leek.toml")));
+        assert!(!is_watched(Path::new("/ais/notes.txt")));
+    }
+
+    #[test]
+    fn report_changes() {
+        let root = tempfile::tempdir().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = FileWatcher::new(move |events| sender.send(events).unwrap()).unwrap();
+        watcher.watch(root.path());
+
+        let path = root.path().join("main.leek");
+        std::fs::write(&path, "var a = 1;").unwrap();
+        std::fs::write(root.path().join("notes.txt"), "").unwrap();
+        let events = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(events, vec![FileEvent { uri: uri::from_path(&path).unwrap(), typ: FileChangeType::Created }]);
+
+        std::fs::remove_file(&path).unwrap();
+        let events = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(events[0].typ, FileChangeType::Deleted);
+    }
+}
@@ -0,0 +1,163 @@
+//! Workspace folders opened by the client.
+//!
+//! Each folder is a separate root with its own project: a document belongs
+//! to the innermost root containing it, and symbols, includes and settings
+//! never leak from one root to another.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::lsp::{DocumentUri, WorkspaceFolder};
+use crate::project::Project;
+use crate::settings::Settings;
+use crate::uri;
+
+/// A workspace folder and its project file, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Root {
+    pub folder: WorkspaceFolder,
+    pub project: Option<Project>,
+}
+
+impl Root {
+    pub fn new(folder: WorkspaceFolder) -> Self {
+        Root { folder, project: None }
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.folder.uri
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        uri::to_path(&self.folder.uri)
+    }
+
+    pub fn contains(&self, uri: &str) -> bool {
+        uri::contains(&self.folder.uri, uri)
+    }
+
+    /// Resolve `include(path)` written in the document `from`: relative to
+    /// the directory of `from` first, then to the include paths. Files
+    /// outside of the root are never resolved.
+    pub fn resolve_include(&self, from: &str, path: &str, settings: &Settings) -> Option<DocumentUri> {
+        let root = self.path()?;
+        let directory = uri::to_path(from)?.parent()?.to_path_buf();
+        let candidates = std::iter::once(directory).chain(settings.include_paths.iter().map(|dir| root.join(dir)));
+        for directory in candidates {
+            let candidate = normalize(&directory.join(path));
+            if candidate.starts_with(&root) && candidate.is_file() {
+                return uri::from_path(&candidate);
+            }
+        }
+        None
+    }
+}
+
+/// Remove `.` and `..` components without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Workspace {
+    roots: Vec<Root>,
+}
+
+impl Workspace {
+    pub fn roots(&self) -> &[Root] {
+        &self.roots
+    }
+
+    pub fn folders(&self) -> Vec<WorkspaceFolder> {
+        self.roots.iter().map(|root| root.folder.clone()).collect()
+    }
+
+    /// Add the folder `folder`, unless it is already open.
+    pub fn add(&mut self, folder: WorkspaceFolder) -> bool {
+        if self.get(&folder.uri).is_some() {
+            return false;
+        }
+        self.roots.push(Root::new(folder));
+        true
+    }
+
+    pub fn remove(&mut self, uri: &str) -> Option<Root> {
+        let index = self.roots.iter().position(|root| same_folder(root.uri(), uri))?;
+        Some(self.roots.remove(index))
+    }
+
+    /// Root of the folder `uri`.
+    pub fn get(&self, uri: &str) -> Option<&Root> {
+        self.roots.iter().find(|root| same_folder(root.uri(), uri))
+    }
+
+    pub fn get_mut(&mut self, uri: &str) -> Option<&mut Root> {
+        self.roots.iter_mut().find(|root| same_folder(root.uri(), uri))
+    }
+
+    /// Innermost root containing the document `uri`.
+    pub fn root_of(&self, uri: &str) -> Option<&Root> {
+        self.roots
+            .iter()
+            .filter(|root| root.contains(uri))
+            .max_by_key(|root| root.uri().len())
+    }
+}
+
+fn same_folder(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn folder(uri: &str) -> WorkspaceFolder {
+        WorkspaceFolder { uri: uri.to_string(), name: uri::file_name(uri).to_string() }
+    }
+
+    #[test]
+    fn roots() {
+        let mut workspace = Workspace::default();
+        assert!(workspace.add(folder("file:///ais")));
+        assert!(workspace.add(folder("file:///ais/lib/")));
+        assert!(!workspace.add(folder("file:///ais/")));
+        assert_eq!(workspace.root_of("file:///ais/lib/util.leek").unwrap().uri(), "file:///ais/lib/");
+        assert_eq!(workspace.root_of("file:///ais/main.leek").unwrap().uri(), "file:///ais");
+        assert!(workspace.root_of("file:///aisle.leek").is_none());
+
+        assert!(workspace.remove("file:///ais/lib").is_some());
+        assert_eq!(workspace.root_of("file:///ais/lib/util.leek").unwrap().uri(), "file:///ais");
+    }
+
+    #[test]
+    fn includes_stay_in_their_root() {
+        let directory = tempfile::tempdir().unwrap();
+        let first = directory.path().join("first");
+        let second = directory.path().join("second");
+        fs::create_dir_all(first.join("lib")).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(first.join("lib/util.leek"), "").unwrap();
+        fs::write(second.join("shared.leek"), "").unwrap();
+
+        let root = Root::new(folder(&uri::from_path(&first).unwrap()));
+        let main = uri::from_path(&first.join("main.leek")).unwrap();
+        let settings = Settings { include_paths: vec!["lib".to_string()], ..Settings::default() };
+        assert_eq!(
+            root.resolve_include(&main, "util.leek", &settings),
+            uri::from_path(&first.join("lib/util.leek"))
+        );
+        assert_eq!(root.resolve_include(&main, "lib/util.leek", &Settings::default()), uri::from_path(&first.join("lib/util.leek")));
+        assert_eq!(root.resolve_include(&main, "../second/shared.leek", &settings), None);
+    }
+}
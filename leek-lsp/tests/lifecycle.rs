@@ -0,0 +1,39 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn framed(body: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+/// Run the server on `messages` and return its exit code.
+fn exit_code(messages: &[&str]) -> Option<i32> {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_leek-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = server.stdin.take().unwrap();
+    for message in messages {
+        stdin.write_all(framed(message).as_bytes()).unwrap();
+    }
+    stdin.flush().unwrap();
+    let status = server.wait().unwrap();
+    status.code()
+}
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"processId":null,"rootUri":null,"capabilities":{}}}"#;
+const INITIALIZED: &str = r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#;
+const SHUTDOWN: &str = r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#;
+const EXIT: &str = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+
+#[test]
+fn exit_after_shutdown() {
+    assert_eq!(exit_code(&[INITIALIZE, INITIALIZED, SHUTDOWN, EXIT]), Some(0));
+}
+
+#[test]
+fn exit_without_shutdown() {
+    assert_eq!(exit_code(&[INITIALIZE, INITIALIZED, EXIT]), Some(1));
+    assert_eq!(exit_code(&[EXIT]), Some(1));
+}
@@ -0,0 +1,124 @@
+//! Round-trip of the protocol types against messages taken from the LSP
+//! 3.17 specification and from real clients.
+
+use leek_lsp::lsp::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("cannot read {}: {}", path, err));
+    serde_json::from_str(&text).unwrap()
+}
+
+/// Deserialize `value` as `T` and check that serializing it gives `value`
+/// back.
+fn round_trip<T: DeserializeOwned + Serialize>(value: Value) -> T {
+    let typed: T = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&typed).unwrap(), value);
+    typed
+}
+
+#[test]
+fn initialize_params() {
+    let params: InitializeParams = round_trip(fixture("initialize_params"));
+    assert_eq!(params.trace, Some(TraceValue::Verbose));
+    assert_eq!(params.workspace_folders.unwrap()[0].name, "ais");
+    let text_document = params.capabilities.text_document.unwrap();
+    assert_eq!(text_document.folding_range.unwrap().line_folding_only, Some(true));
+    assert_eq!(text_document.definition.unwrap().link_support, Some(true));
+    let item = text_document.completion.unwrap().completion_item.unwrap();
+    assert_eq!(item.documentation_format, Some(vec![MarkupKind::Markdown, MarkupKind::PlainText]));
+}
+
+#[test]
+fn initialize_result() {
+    let result: InitializeResult = round_trip(fixture("initialize_result"));
+    assert!(matches!(result.capabilities.text_document_sync, Some(Either::Left(_))));
+}
+
+#[test]
+fn text_document_messages() {
+    let params: DidChangeTextDocumentParams = round_trip(fixture("did_change"));
+    assert_eq!(params.text_document.version, 7);
+    assert_eq!(params.content_changes[1].range, None);
+
+    let params: PublishDiagnosticsParams = round_trip(fixture("publish_diagnostics"));
+    assert_eq!(params.diagnostics[0].tags, Some(vec![DiagnosticTag::Unnecessary]));
+    assert_eq!(params.diagnostics[1].code, Some(Either::Left(1)));
+}
+
+#[test]
+fn workspace_edit() {
+    let edit: WorkspaceEdit = round_trip(fixture("workspace_edit"));
+    let changes = edit.document_changes.unwrap();
+    assert!(matches!(changes[0], DocumentChange::Edit(_)));
+    assert!(matches!(changes[3], DocumentChange::Operation(ResourceOperation::Delete(_))));
+    match &changes[4] {
+        DocumentChange::Edit(edit) => assert_eq!(edit.text_document.version, None),
+        change => panic!("unexpected change {:?}", change),
+    }
+}
+
+#[test]
+fn language_features() {
+    let links: Vec<LocationLink> = round_trip(fixture("location_links"));
+    assert_eq!(links[0].target_selection_range.start, Position { line: 10, character: 9 });
+    let list: CompletionList = round_trip(fixture("completion_list"));
+    assert_eq!(list.items[0].insert_text_format, Some(InsertTextFormat::Snippet));
+    round_trip::<Hover>(fixture("hover"));
+    round_trip::<Vec<DocumentSymbol>>(fixture("document_symbols"));
+}
+
+#[test]
+fn registration_and_progress() {
+    let params: RegistrationParams = round_trip(fixture("register_capability"));
+    let options: DidChangeWatchedFilesRegistrationOptions =
+        round_trip(params.registrations[0].register_options.clone().unwrap());
+    assert_eq!(options.watchers[1].kind, Some(watch_kind::CREATE | watch_kind::CHANGE | watch_kind::DELETE));
+    let progress: Vec<ProgressParams<WorkDoneProgress>> = round_trip(fixture("progress"));
+    assert!(matches!(progress[2].value, WorkDoneProgress::End(_)));
+}
+
+#[test]
+fn unknown_client_values_are_ignored() {
+    let capabilities: TextDocumentClientCapabilities = serde_json::from_value(json!({
+        "completion": {
+            "completionItem": { "documentationFormat": ["markdown", "html"] },
+            "completionItemKind": { "valueSet": [1, 3, 42] },
+            "insertTextMode": 7
+        },
+        "codeAction": { "codeActionLiteralSupport": { "codeActionKind": { "valueSet": ["quickfix"] } } }
+    }))
+    .unwrap();
+    let completion = capabilities.completion.unwrap();
+    assert_eq!(completion.completion_item.unwrap().documentation_format, Some(vec![MarkupKind::Markdown]));
+    assert_eq!(
+        completion.completion_item_kind.unwrap().value_set,
+        vec![CompletionItemKind::Text, CompletionItemKind::Function]
+    );
+    assert_eq!(completion.insert_text_mode, None);
+}
+
+#[test]
+fn required_nullable_fields_are_written() {
+    let params = InitializeParams::<Value> {
+        process_id: None,
+        client_info: None,
+        locale: None,
+        root_path: None,
+        root_uri: None,
+        initialization_options: None,
+        capabilities: ClientCapabilities::default(),
+        trace: None,
+        workspace_folders: None,
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+    assert_eq!(
+        serde_json::to_value(params).unwrap(),
+        json!({ "processId": null, "rootUri": null, "capabilities": {} })
+    );
+    let identifier = OptionalVersionedTextDocumentIdentifier { uri: "file:///a.leek".to_string(), version: None };
+    assert_eq!(serde_json::to_value(identifier).unwrap(), json!({ "uri": "file:///a.leek", "version": null }));
+}
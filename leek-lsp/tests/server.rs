@@ -0,0 +1,98 @@
+//! The server driven end to end by the in-process test client.
+
+mod support;
+
+use serde_json::json;
+use support::{Fixture, TestClient, MAIN};
+
+#[test]
+fn initialize_and_shutdown() {
+    let mut client = TestClient::new();
+    let result = client.initialize(json!({ "processId": null, "rootUri": null, "capabilities": {} }));
+    assert_eq!(result["serverInfo"]["name"], "leek-lsp");
+    assert_eq!(client.request("workspace/symbol", json!({ "query": "" })), Ok(json!([])));
+    assert_eq!(client.shutdown(), 0);
+}
+
+#[test]
+fn requests_before_initialize() {
+    let mut client = TestClient::new();
+    let error = client.request("workspace/symbol", json!({ "query": "" })).unwrap_err();
+    assert_eq!(error["code"], -32002);
+}
+
+#[test]
+fn publish_diagnostics() {
+    let mut client = TestClient::initialized(json!({}));
+    client.open(MAIN, "var a = ;");
+    assert_eq!(client.diagnostics(MAIN).len(), 1);
+    client.change(MAIN, 2, "var a = 1;");
+    assert!(client.diagnostics(MAIN).is_empty());
+    client.close(MAIN);
+    assert!(client.diagnostics(MAIN).is_empty());
+}
+
+#[test]
+fn answer_server_requests() {
+    let mut client = TestClient::new();
+    client.on_request(|method, _| match method {
+        "workspace/configuration" => json!([{ "languageVersion": 2 }]),
+        _ => json!(null),
+    });
+    client.initialize(json!({
+        "processId": null,
+        "rootUri": null,
+        "capabilities": { "workspace": { "configuration": true } },
+    }));
+    let params = client.expect("workspace/configuration");
+    assert_eq!(params["items"][0]["section"], "leekscript");
+    client.open(MAIN, "var a = 1;");
+    client.diagnostics(MAIN);
+    assert_eq!(client.server().settings(MAIN).language_version, 2);
+}
+
+#[test]
+fn fixtures_with_cursors() {
+    let fixture = Fixture::new("var é = $01;\n$0var b = 2;");
+    assert_eq!(fixture.text, "var é = 1;\nvar b = 2;");
+    assert_eq!(serde_json::to_value(&fixture.cursors).unwrap(), json!([
+        { "line": 0, "character": 8 },
+        { "line": 1, "character": 0 },
+    ]));
+
+    let fixture = Fixture::load("attack.leek");
+    assert!(!fixture.text.contains("$0"));
+    assert_eq!(fixture.position_params(MAIN), json!({
+        "textDocument": { "uri": MAIN },
+        "position": { "line": 4, "character": 11 },
+    }));
+
+    let mut client = TestClient::initialized(json!({}));
+    client.open(MAIN, &fixture.text);
+    assert!(client.diagnostics(MAIN).is_empty());
+    let symbols = client.request("workspace/symbol", json!({ "query": "atk" })).unwrap();
+    assert_eq!(symbols[0]["name"], "attack");
+}
+
+#[test]
+fn checks_on_save() {
+    let mut client = TestClient::initialized(json!({
+        "textDocument": { "publishDiagnostics": { "tagSupport": { "valueSet": [1, 2] } } },
+    }));
+    client.open(MAIN, "function attack() { return 1; }\nattack();");
+    assert!(client.diagnostics(MAIN).is_empty());
+
+    // A burst of changes is answered once, with the syntax errors only.
+    client.change(MAIN, 2, "function attack() { return 1; }\nvar a = ;");
+    client.change(MAIN, 3, "function attack() { return 1; }\ndebug(1);");
+    let params = client.expect("textDocument/publishDiagnostics");
+    assert_eq!(params["version"], 3);
+    assert_eq!(params["diagnostics"], json!([]));
+    assert!(client.drain().is_empty());
+
+    client.save(MAIN);
+    let diagnostics = client.diagnostics(MAIN);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["message"], "function `attack` is never called");
+    assert_eq!(diagnostics[0]["tags"], json!([1]));
+}
@@ -0,0 +1,361 @@
+//! Harness driving the server in-process, the way an editor would.
+//!
+//! A [`TestClient`] runs the server on its own thread and talks to it over
+//! an in-memory transport, with the same framing as on stdio:
+//!
+//! ```ignore
+//! let mut client = TestClient::initialized(json!({}));
+//! let fixture = Fixture::new("var $0life = 1;");
+//! client.open(MAIN, &fixture.text);
+//! assert!(client.diagnostics(MAIN).is_empty());
+//! let symbols = client.request("workspace/symbol", json!({ "query": "life" })).unwrap();
+//! ```
+//!
+//! Modules of the `tests` directory use it with `mod support;`; not every
+//! one of them needs every helper.
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::io::{self, BufReader, Read, Write};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use leek_lsp::client::Client;
+use leek_lsp::documents::Document;
+use leek_lsp::lsp::Position;
+use leek_lsp::server::LeekLSP;
+use leek_lsp::transport;
+use serde_json::{json, Value};
+
+/// URI of the main file of the tests.
+pub const MAIN: &str = "file:///ais/main.leek";
+
+/// How long to wait for a message before failing the test.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Writing end of an in-memory pipe.
+pub struct PipeWriter(Sender<Vec<u8>>);
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.send(buf.to_vec()).map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reading end of an in-memory pipe, at the end of the stream once the
+/// writer is dropped.
+pub struct PipeReader {
+    receiver: Receiver<Vec<u8>>,
+    buffer: VecDeque<u8>,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buffer.is_empty() {
+            match self.receiver.recv() {
+                Ok(bytes) => self.buffer.extend(bytes),
+                Err(_) => return Ok(0),
+            }
+        }
+        self.buffer.read(buf)
+    }
+}
+
+pub fn pipe() -> (PipeWriter, PipeReader) {
+    let (sender, receiver) = channel();
+    (PipeWriter(sender), PipeReader { receiver, buffer: VecDeque::new() })
+}
+
+/// Answers of the client to the requests of the server, by method.
+type Responder = Box<dyn FnMut(&str, &Value) -> Value + Send>;
+
+/// Default answers: no settings, and success for everything else.
+fn default_response(method: &str, params: &Value) -> Value {
+    match method {
+        "workspace/configuration" => {
+            let items = params["items"].as_array().map_or(0, Vec::len);
+            Value::Array(vec![Value::Null; items])
+        }
+        _ => Value::Null,
+    }
+}
+
+/// An editor connected to a server running in-process.
+///
+/// Requests of the server are answered on the fly, by default with
+/// [`default_response`], and also recorded with the notifications so that
+/// tests can look at them with [`TestClient::expect`].
+pub struct TestClient {
+    server: LeekLSP,
+    writer: Arc<Mutex<Option<PipeWriter>>>,
+    messages: Receiver<Value>,
+    /// Messages received while waiting for something else.
+    pending: VecDeque<Value>,
+    responder: Arc<Mutex<Responder>>,
+    next_id: u64,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl TestClient {
+    /// Start a server, not yet initialized.
+    pub fn new() -> Self {
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        let (client_writer, server_input) = pipe();
+        let (server_output, client_reader) = pipe();
+        let scheduler = server.scheduler(2);
+        let server_thread = thread::Builder::new()
+            .name("test-server".to_string())
+            .spawn(move || transport::serve(scheduler, outgoing, server_input, server_output))
+            .unwrap();
+
+        let writer = Arc::new(Mutex::new(Some(client_writer)));
+        let responder: Arc<Mutex<Responder>> = Arc::new(Mutex::new(Box::new(default_response)));
+        let (messages, received) = channel();
+        let reader_thread = {
+            let writer = writer.clone();
+            let responder = responder.clone();
+            thread::Builder::new()
+                .name("test-client".to_string())
+                .spawn(move || {
+                    let mut reader = BufReader::new(client_reader);
+                    while let Ok(Some(message)) = transport::read_message(&mut reader) {
+                        let message: Value = serde_json::from_str(&message).unwrap();
+                        if message.get("method").is_some() && message.get("id").is_some() {
+                            let method = message["method"].as_str().unwrap_or_default();
+                            let result = (responder.lock().unwrap())(method, &message["params"]);
+                            send(&writer, json!({ "jsonrpc": "2.0", "id": message["id"], "result": result }));
+                        }
+                        if messages.send(message).is_err() {
+                            break;
+                        }
+                    }
+                })
+                .unwrap()
+        };
+
+        TestClient {
+            server,
+            writer,
+            messages: received,
+            pending: VecDeque::new(),
+            responder,
+            next_id: 1,
+            threads: vec![server_thread, reader_thread],
+        }
+    }
+
+    /// Start a server and go through `initialize` and `initialized` with
+    /// the client capabilities `capabilities`.
+    pub fn initialized(capabilities: Value) -> Self {
+        let mut client = TestClient::new();
+        client.initialize(json!({ "processId": null, "rootUri": null, "capabilities": capabilities }));
+        client
+    }
+
+    /// The server under test, to look at its state.
+    pub fn server(&self) -> &LeekLSP {
+        &self.server
+    }
+
+    /// Answer the requests of the server with `responder` from now on.
+    pub fn on_request<F>(&self, responder: F)
+    where
+        F: FnMut(&str, &Value) -> Value + Send + 'static,
+    {
+        *self.responder.lock().unwrap() = Box::new(responder);
+    }
+
+    /// Send `initialize` with `params`, then `initialized`, and return the
+    /// result of `initialize`.
+    pub fn initialize(&mut self, params: Value) -> Value {
+        let result = self.request("initialize", params).expect("initialize failed");
+        self.notify("initialized", json!({}));
+        result
+    }
+
+    /// Send a request and wait for its response: the result, or the error
+    /// object.
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value, Value> {
+        let id = self.send_request(method, params);
+        let response = self.wait_for(&format!("the response to {}", method), |message| {
+            message.get("method").is_none() && message["id"] == id
+        });
+        match response.get("error") {
+            Some(error) => Err(error.clone()),
+            None => Ok(response["result"].clone()),
+        }
+    }
+
+    /// Send a request without waiting for its response, and return its id.
+    pub fn send_request(&mut self, method: &str, params: Value) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        send(&self.writer, json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        id
+    }
+
+    pub fn notify(&mut self, method: &str, params: Value) {
+        send(&self.writer, json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    /// Open the document `uri` with the content `text`.
+    pub fn open(&mut self, uri: &str, text: &str) {
+        self.notify("textDocument/didOpen", json!({
+            "textDocument": { "uri": uri, "languageId": "leekscript", "version": 1, "text": text },
+        }));
+    }
+
+    /// Replace the whole content of the document `uri`.
+    pub fn change(&mut self, uri: &str, version: i32, text: &str) {
+        self.notify("textDocument/didChange", json!({
+            "textDocument": { "uri": uri, "version": version },
+            "contentChanges": [{ "text": text }],
+        }));
+    }
+
+    pub fn save(&mut self, uri: &str) {
+        self.notify("textDocument/didSave", json!({ "textDocument": { "uri": uri } }));
+    }
+
+    pub fn close(&mut self, uri: &str) {
+        self.notify("textDocument/didClose", json!({ "textDocument": { "uri": uri } }));
+    }
+
+    /// Wait for the next notification or request of the server with the
+    /// method `method`, and return its parameters.
+    pub fn expect(&mut self, method: &str) -> Value {
+        self.wait_for(method, |message| message["method"] == method)["params"].take()
+    }
+
+    /// Wait for the next diagnostics published for `uri`.
+    pub fn diagnostics(&mut self, uri: &str) -> Vec<Value> {
+        let mut params = self.wait_for(&format!("diagnostics of {}", uri), |message| {
+            message["method"] == "textDocument/publishDiagnostics" && message["params"]["uri"] == uri
+        })["params"]
+            .take();
+        match params["diagnostics"].take() {
+            Value::Array(diagnostics) => diagnostics,
+            diagnostics => panic!("invalid diagnostics {}", diagnostics),
+        }
+    }
+
+    /// Messages received from the server and not looked at yet, after
+    /// waiting a little for the ones on their way.
+    pub fn drain(&mut self) -> Vec<Value> {
+        while let Ok(message) = self.messages.recv_timeout(Duration::from_millis(100)) {
+            self.pending.push_back(message);
+        }
+        self.pending.drain(..).collect()
+    }
+
+    /// Remove and return the first message satisfying `wanted`, received
+    /// before or within the timeout.
+    fn wait_for(&mut self, what: &str, wanted: impl Fn(&Value) -> bool) -> Value {
+        if let Some(index) = self.pending.iter().position(&wanted) {
+            return self.pending.remove(index).unwrap();
+        }
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.messages.recv_timeout(timeout) {
+                Ok(message) if wanted(&message) => return message,
+                Ok(message) => self.pending.push_back(message),
+                Err(RecvTimeoutError::Timeout) => {
+                    panic!("timed out waiting for {}, received {:?}", what, self.pending)
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    panic!("server stopped while waiting for {}, received {:?}", what, self.pending)
+                }
+            }
+        }
+    }
+
+    /// Shut the server down and return the exit code it would use. `exit`
+    /// is not sent, as it ends the process: the connection is closed
+    /// instead.
+    pub fn shutdown(mut self) -> i32 {
+        self.request("shutdown", Value::Null).expect("shutdown failed");
+        self.stop();
+        self.server.lifecycle().exit_code()
+    }
+
+    /// Close the connection and wait for the server to stop.
+    fn stop(&mut self) {
+        self.writer.lock().unwrap().take();
+        for thread in self.threads.drain(..) {
+            thread.join().unwrap();
+        }
+    }
+}
+
+impl Drop for TestClient {
+    fn drop(&mut self) {
+        // Don't hide the reason of a failure behind a second panic.
+        if !thread::panicking() {
+            self.stop();
+        }
+    }
+}
+
+fn send(writer: &Mutex<Option<PipeWriter>>, message: Value) {
+    if let Some(writer) = writer.lock().unwrap().as_mut() {
+        transport::write_message(writer, &message.to_string()).unwrap();
+    }
+}
+
+/// Marker of a position in a fixture.
+pub const CURSOR: &str = "$0";
+
+/// Source text with positions marked by `$0`, for the features working at
+/// a position.
+#[derive(Clone, Debug)]
+pub struct Fixture {
+    /// Text without the markers.
+    pub text: String,
+    /// Marked positions, in order.
+    pub cursors: Vec<Position>,
+}
+
+impl Fixture {
+    pub fn new(source: &str) -> Self {
+        let mut text = String::with_capacity(source.len());
+        let mut offsets = Vec::new();
+        for (index, part) in source.split(CURSOR).enumerate() {
+            if index > 0 {
+                offsets.push(text.len());
+            }
+            text.push_str(part);
+        }
+        let document = Document::from_text(MAIN.to_string(), text.clone());
+        let cursors = offsets.into_iter().map(|offset| document.position_at(offset)).collect();
+        Fixture { text, cursors }
+    }
+
+    /// Read `tests/fixtures/<name>`.
+    pub fn load(name: &str) -> Self {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let source = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("cannot read {}: {}", path, err));
+        Fixture::new(&source)
+    }
+
+    /// The only marked position.
+    pub fn cursor(&self) -> Position {
+        match self.cursors[..] {
+            [cursor] => cursor,
+            _ => panic!("expected a single {} in the fixture, found {}", CURSOR, self.cursors.len()),
+        }
+    }
+
+    /// `textDocument/*` position parameters for the cursor in `uri`.
+    pub fn position_params(&self, uri: &str) -> Value {
+        json!({ "textDocument": { "uri": uri }, "position": self.cursor() })
+    }
+}
@@ -0,0 +1,113 @@
+//! The server over real sockets on the loopback interface.
+
+use std::io::BufReader;
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+
+use leek_lsp::client::Client;
+use leek_lsp::scheduler::Scheduler;
+use leek_lsp::server::LeekLSP;
+use leek_lsp::transport::{self, websocket, Outgoing, Transport};
+use serde_json::{json, Value};
+use tungstenite::Message;
+
+/// Run a server on its own thread, serving with `serve`.
+fn spawn_server<F>(serve: F) -> JoinHandle<()>
+where
+    F: FnOnce(Scheduler, std::sync::mpsc::Receiver<Outgoing>) + Send + 'static,
+{
+    thread::spawn(move || {
+        let (sender, outgoing) = transport::outgoing();
+        let server = LeekLSP::new(Client::new(sender));
+        serve(server.scheduler(2), outgoing);
+    })
+}
+
+fn initialize() -> Value {
+    json!({
+        "jsonrpc": "2.0", "id": 1, "method": "initialize",
+        "params": { "processId": null, "rootUri": null, "capabilities": {} },
+    })
+}
+
+fn workspace_symbol() -> Value {
+    json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol", "params": { "query": "" } })
+}
+
+/// Initialize the server at the other end of `stream` and query it.
+fn exchange_tcp(stream: &TcpStream) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream.try_clone().unwrap();
+    let mut request = |message: Value| {
+        transport::write_message(&mut writer, &message.to_string()).unwrap();
+        loop {
+            let response: Value = serde_json::from_str(&transport::read_message(&mut reader).unwrap().unwrap()).unwrap();
+            if response["id"] == message["id"] {
+                return response;
+            }
+        }
+    };
+    let response = request(initialize());
+    assert_eq!(response["result"]["serverInfo"]["name"], "leek-lsp");
+    assert_eq!(request(workspace_symbol())["result"], json!([]));
+}
+
+#[test]
+fn tcp_server() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = spawn_server(move |scheduler, outgoing| {
+        let (stream, _) = listener.accept().unwrap();
+        transport::serve_tcp(scheduler, outgoing, stream).unwrap();
+    });
+
+    let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+    exchange_tcp(&stream);
+    drop(stream);
+    server.join().unwrap();
+}
+
+#[test]
+fn tcp_client() {
+    // The editor listens, the server connects to it.
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = spawn_server(move |scheduler, outgoing| {
+        Transport::Tcp { port, listen: false }.serve(scheduler, outgoing).unwrap();
+    });
+
+    let (stream, _) = listener.accept().unwrap();
+    exchange_tcp(&stream);
+    drop(stream);
+    server.join().unwrap();
+}
+
+#[test]
+fn websocket() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = spawn_server(move |scheduler, outgoing| {
+        websocket::serve_listener(scheduler, outgoing, listener).unwrap();
+    });
+
+    let stream = TcpStream::connect(address).unwrap();
+    let (mut socket, _) = tungstenite::client(format!("ws://{}", address), stream).unwrap();
+    let mut request = |message: Value| {
+        socket.send(Message::Text(message.to_string())).unwrap();
+        loop {
+            if let Message::Text(text) = socket.read().unwrap() {
+                let response: Value = serde_json::from_str(&text).unwrap();
+                if response["id"] == message["id"] {
+                    return response;
+                }
+            }
+        }
+    };
+    assert_eq!(request(initialize())["result"]["capabilities"]["textDocumentSync"]["change"], 2);
+    assert_eq!(request(workspace_symbol())["result"], json!([]));
+
+    socket.close(None).unwrap();
+    // Wait for the server to acknowledge the close.
+    while socket.read().is_ok() {}
+    server.join().unwrap();
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::ops::Range;
+
+use pest::error::{Error, ErrorVariant, InputLocation};
+
+use crate::Rule;
+
+/// The different kinds of syntax errors reported by the parser.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SyntaxErrorKind {
+    /// A token that cannot appear at this position.
+    UnexpectedToken,
+    /// The file ends in the middle of a construct.
+    UnexpectedEof,
+}
+
+impl SyntaxErrorKind {
+    /// Stable code identifying the kind of error, reported to editors.
+    pub fn code(self) -> &'static str {
+        match self {
+            SyntaxErrorKind::UnexpectedToken => "E0001",
+            SyntaxErrorKind::UnexpectedEof => "E0002",
+        }
+    }
+}
+
+/// A syntax error located by its byte span in the source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub kind: SyntaxErrorKind,
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.message, self.span.start, self.span.end)
+    }
+}
+
+/// Human readable name of a rule, used in error messages.
+pub fn describe(rule: Rule) -> &'static str {
+    match rule {
+        Rule::Ident => "identifier",
+        Rule::Keyword => "keyword",
+        Rule::Number => "number",
+        Rule::EOI => "end of file",
+        Rule::Prog | Rule::Stmt => "statement",
+        Rule::Declr | Rule::ForDeclr => "variable declaration",
+        Rule::Global => "global declaration",
+        Rule::Affect | Rule::ForAffect => "assignment",
+        Rule::IfElse | Rule::If => "`if`",
+        Rule::Else => "`else`",
+        Rule::While => "`while`",
+        Rule::For | Rule::ForIn | Rule::ForLoop => "`for`",
+        Rule::Block => "block",
+        Rule::Class => "class",
+        Rule::Field | Rule::Method => "class member",
+        Rule::Args => "parameters",
+        Rule::Arg => "parameter",
+        Rule::Params => "arguments",
+        Rule::Defun => "function",
+        Rule::Call => "function call",
+        Rule::Return => "`return`",
+        Rule::Include => "`include`",
+        Rule::Path => "file path",
+        Rule::Expr => "expression",
+        _ => "token",
+    }
+}
+
+impl SyntaxError {
+    /// Build a syntax error from a pest error raised while parsing
+    /// `source[offset..]`.
+    pub(crate) fn from_pest(error: Error<Rule>, source: &str, offset: usize) -> Self {
+        let mut pos = match error.location {
+            InputLocation::Pos(pos) => pos,
+            InputLocation::Span((start, _)) => start,
+        };
+        let mut expected = Vec::new();
+        // With error details enabled, pest also tracks the literal tokens
+        // attempted at the furthest position, which is more precise than
+        // the rule reported by the error itself.
+        if let Some(attempts) = error.parse_attempts() {
+            if attempts.max_position > pos {
+                pos = attempts.max_position;
+                for token in attempts.expected_tokens() {
+                    let token = token.to_string();
+                    match token.as_str() {
+                        // Whitespace and comments may appear anywhere.
+                        " " | "\t" | "\n" | "\r" | "//" | "/*" => {}
+                        "0..9" => expected.push(describe(Rule::Number).to_string()),
+                        "a..z" | "A..Z" | "_" => expected.push(describe(Rule::Ident).to_string()),
+                        _ => expected.push(format!("`{}`", token)),
+                    }
+                }
+            }
+        }
+        let start = offset + pos;
+        let start = start + (source[start..].len() - source[start..].trim_start().len());
+        let token = token_at(source, start);
+        let (kind, end) = if token.is_empty() {
+            (SyntaxErrorKind::UnexpectedEof, start)
+        } else {
+            (SyntaxErrorKind::UnexpectedToken, start + token.len())
+        };
+        match error.variant {
+            ErrorVariant::ParsingError { positives, .. } => {
+                if expected.is_empty() {
+                    expected = positives.into_iter().map(|rule| describe(rule).to_string()).collect();
+                }
+            }
+            ErrorVariant::CustomError { message } => {
+                return SyntaxError { kind, span: start..end, message };
+            }
+        }
+        expected.sort();
+        expected.dedup();
+        let message = match (kind, expected.is_empty()) {
+            (SyntaxErrorKind::UnexpectedEof, true) => "unexpected end of file".to_string(),
+            (SyntaxErrorKind::UnexpectedEof, false) => {
+                format!("unexpected end of file, expected {}", expected.join(" or "))
+            }
+            (_, true) => format!("unexpected `{}`", token),
+            (_, false) => format!("expected {}, found `{}`", expected.join(" or "), token),
+        };
+        SyntaxError { kind, span: start..end, message }
+    }
+}
+
+/// The token starting at `pos`: a word, or a single other character.
+fn token_at(source: &str, pos: usize) -> &str {
+    let rest = &source[pos..];
+    let word = rest
+        .char_indices()
+        .find(|(_, c)| !c.is_alphanumeric() && *c != '_')
+        .map_or(rest.len(), |(i, _)| i);
+    if word > 0 {
+        &rest[..word]
+    } else {
+        rest.chars().next().map_or("", |c| &rest[..c.len_utf8()])
+    }
+}
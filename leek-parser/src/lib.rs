@@ -1,12 +1,66 @@
-use pest::Parser;
+pub use pest::Parser;
+pub use pest::iterators::{Pair, Pairs};
+
+use std::sync::Once;
 
 #[macro_use]
 extern crate pest_derive;
 
+mod error;
+
+pub use error::{describe, SyntaxError, SyntaxErrorKind};
+
+/// Maximum number of syntax errors reported for a single file.
+const MAX_ERRORS: usize = 100;
+
 #[derive(Parser)]
 #[grammar = "leekscript.pest"]
 pub struct LeekParser;
 
+/// Ask pest for the detail of what was expected on errors. The setting is
+/// global to pest, so it is turned on once, before the first parse of
+/// either entry point, and errors have the same detail whichever runs.
+fn enable_error_detail() {
+    static ENABLE: Once = Once::new();
+    ENABLE.call_once(|| pest::set_error_detail(true));
+}
+
+/// Parse a whole LeekScript file.
+pub fn parse(source: &str) -> Result<Pairs<'_, Rule>, SyntaxError> {
+    enable_error_detail();
+    LeekParser::parse(Rule::File, source).map_err(|err| SyntaxError::from_pest(err, source, 0))
+}
+
+/// Collect every syntax error of `source`.
+///
+/// After an error, parsing resumes after the next `;`, `}` or line break,
+/// so a single mistake does not hide the following ones.
+pub fn syntax_errors(source: &str) -> Vec<SyntaxError> {
+    enable_error_detail();
+    let mut errors = Vec::new();
+    let mut offset = 0;
+    let mut result = LeekParser::parse(Rule::File, source).map(|_| ());
+    while let Err(err) = result {
+        let error = SyntaxError::from_pest(err, source, offset);
+        offset = resume_offset(source, error.span.start);
+        errors.push(error);
+        if offset >= source.len() || errors.len() >= MAX_ERRORS {
+            break;
+        }
+        result = LeekParser::parse(Rule::Recovery, &source[offset..]).map(|_| ());
+    }
+    errors
+}
+
+/// Position right after the first statement boundary following `pos`.
+fn resume_offset(source: &str, pos: usize) -> usize {
+    // Always make progress, even when the error points at a boundary.
+    let from = pos + source[pos..].chars().next().map_or(0, char::len_utf8);
+    source[from..]
+        .find([';', '}', '\n'])
+        .map_or(source.len(), |i| from + i + 1)
+}
+
 #[test]
 fn test_main() {
     let successful_parse = LeekParser::parse(
@@ -21,3 +75,16 @@ fn test_main() {
         Err(err) => println!("{}", err),
     }
 }
+
+#[test]
+fn test_syntax_errors() {
+    assert!(syntax_errors("// empty file\n").is_empty());
+    assert!(syntax_errors("include(\"lib/util.leek\");\n").is_empty());
+    let errors = syntax_errors("var a = ;\nvar b = 2;\nprint(1\n");
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].kind, SyntaxErrorKind::UnexpectedToken);
+    assert_eq!(errors[0].span, 8..9);
+    assert_eq!(errors[0].message, "expected expression, found `;`");
+    assert_eq!(errors[1].kind, SyntaxErrorKind::UnexpectedEof);
+    assert_eq!(errors[1].message, "unexpected end of file, expected `)` or `,`");
+}
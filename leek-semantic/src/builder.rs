@@ -0,0 +1,207 @@
+//! Construction of the semantic model from the syntax tree.
+
+use std::ops::Range;
+
+use leek_parser::{Pair, Pairs, Rule};
+
+use crate::*;
+
+pub(crate) fn build(source: &str, pairs: Pairs<'_, Rule>) -> SemanticModel {
+    let mut builder = Builder { model: SemanticModel::default(), scope: SemanticModel::FILE };
+    builder.model.scopes.push(Scope {
+        kind: ScopeKind::File,
+        parent: None,
+        span: 0..source.len(),
+        isolated: false,
+        declarations: Vec::new(),
+    });
+    for pair in pairs {
+        builder.visit(pair);
+    }
+    builder.model
+}
+
+fn span(pair: &Pair<'_, Rule>) -> Range<usize> {
+    pair.as_span().start()..pair.as_span().end()
+}
+
+struct Builder {
+    model: SemanticModel,
+    /// Scope of the visited pairs.
+    scope: ScopeId,
+}
+
+impl Builder {
+    /// Open a new scope, returning the current one.
+    fn enter(&mut self, kind: ScopeKind, span: Range<usize>, isolated: bool) -> ScopeId {
+        let id = self.model.scopes.len();
+        self.model.scopes.push(Scope { kind, parent: Some(self.scope), span, isolated, declarations: Vec::new() });
+        std::mem::replace(&mut self.scope, id)
+    }
+
+    /// Visit `pairs` in a new scope.
+    fn in_scope<'i, I>(&mut self, kind: ScopeKind, span: Range<usize>, pairs: I)
+    where
+        I: IntoIterator<Item = Pair<'i, Rule>>,
+    {
+        let parent = self.enter(kind, span, false);
+        for pair in pairs {
+            self.visit(pair);
+        }
+        self.scope = parent;
+    }
+
+    fn declare(&mut self, name: &Pair<'_, Rule>, kind: DeclarationKind, full_span: Range<usize>) -> DeclarationId {
+        let id = self.model.declarations.len();
+        self.model.declarations.push(Declaration {
+            name: name.as_str().to_string(),
+            kind,
+            scope: self.scope,
+            span: span(name),
+            full_span,
+            parameters: Vec::new(),
+            required_parameters: 0,
+        });
+        self.model.scopes[self.scope].declarations.push(id);
+        id
+    }
+
+    fn refer(&mut self, name: &Pair<'_, Rule>, kind: ReferenceKind) -> &mut Reference {
+        self.model.references.push(Reference {
+            name: name.as_str().to_string(),
+            kind,
+            scope: self.scope,
+            span: span(name),
+            resolution: Resolution::Unresolved,
+            arguments: None,
+        });
+        self.model.references.last_mut().unwrap()
+    }
+
+    fn visit(&mut self, pair: Pair<'_, Rule>) {
+        let full_span = span(&pair);
+        match pair.as_rule() {
+            Rule::Declr | Rule::Global | Rule::ForDeclr | Rule::ForIn => {
+                let kind = match pair.as_rule() {
+                    Rule::Global => DeclarationKind::Global,
+                    _ => DeclarationKind::Variable,
+                };
+                let mut inner = pair.into_inner();
+                let name = inner.next().unwrap();
+                // The value is computed before the variable exists.
+                for pair in inner {
+                    self.visit(pair);
+                }
+                self.declare(&name, kind, full_span);
+            }
+            Rule::Affect | Rule::ForAffect => {
+                let mut inner = pair.into_inner();
+                self.refer(&inner.next().unwrap(), ReferenceKind::Write);
+                for pair in inner {
+                    self.visit(pair);
+                }
+            }
+            Rule::Call => {
+                let mut inner = pair.into_inner();
+                let name = inner.next().unwrap();
+                let arguments = inner.clone().flat_map(Pair::into_inner).count();
+                self.refer(&name, ReferenceKind::Call).arguments = Some(arguments);
+                for pair in inner {
+                    self.visit(pair);
+                }
+            }
+            Rule::Ident => {
+                self.refer(&pair, ReferenceKind::Read);
+            }
+            Rule::If | Rule::While => {
+                let kind = match pair.as_rule() {
+                    Rule::If => ScopeKind::Block,
+                    _ => ScopeKind::Loop,
+                };
+                let mut inner = pair.into_inner();
+                self.visit(inner.next().unwrap());
+                let body = inner.next().unwrap();
+                self.in_scope(kind, span(&body), body.into_inner());
+            }
+            Rule::Else | Rule::Block => self.in_scope(ScopeKind::Block, full_span, pair.into_inner()),
+            Rule::For => self.in_scope(ScopeKind::Loop, full_span, pair.into_inner()),
+            Rule::Defun | Rule::Method => {
+                let kind = match pair.as_rule() {
+                    Rule::Method => DeclarationKind::Method,
+                    _ => DeclarationKind::Function,
+                };
+                let mut inner = pair.into_inner().peekable();
+                let name = inner.next_if(|pair| pair.as_rule() == Rule::Ident);
+                let (args, body): (Vec<_>, Vec<_>) = inner.partition(|pair| pair.as_rule() == Rule::Args);
+                let mut parameters = Vec::new();
+                let mut required = 0;
+                for arg in args.into_iter().flat_map(Pair::into_inner) {
+                    let arg_span = span(&arg);
+                    let mut inner = arg.into_inner();
+                    let parameter = inner.next().unwrap();
+                    let default = inner.next();
+                    if default.is_none() {
+                        required = parameters.len() + 1;
+                    }
+                    parameters.push((parameter, arg_span, default));
+                }
+                if let Some(name) = &name {
+                    let declaration = self.declare(name, kind, full_span.clone());
+                    let declaration = &mut self.model.declarations[declaration];
+                    declaration.parameters =
+                        parameters.iter().map(|(parameter, _, _)| parameter.as_str().to_string()).collect();
+                    declaration.required_parameters = required;
+                }
+                // Anonymous functions are closures over the enclosing scope.
+                let parent = self.enter(ScopeKind::Function, full_span, name.is_some());
+                // Default values are computed by the function when it is
+                // called, and see the parameters before theirs.
+                for (parameter, arg_span, default) in parameters {
+                    if let Some(default) = default {
+                        self.visit(default);
+                    }
+                    self.declare(&parameter, DeclarationKind::Parameter, arg_span);
+                }
+                for pair in body {
+                    self.visit(pair);
+                }
+                self.scope = parent;
+            }
+            Rule::Class => {
+                let mut inner = pair.into_inner();
+                let name = inner.next().unwrap();
+                self.declare(&name, DeclarationKind::Class, full_span.clone());
+                let mut members = Vec::new();
+                for pair in inner {
+                    match pair.as_rule() {
+                        // The parent class.
+                        Rule::Ident => {
+                            self.refer(&pair, ReferenceKind::Read);
+                        }
+                        _ => members.push(pair),
+                    }
+                }
+                self.in_scope(ScopeKind::Class, full_span, members);
+            }
+            Rule::Field => {
+                let mut inner = pair.into_inner();
+                let name = inner.next().unwrap();
+                self.declare(&name, DeclarationKind::Field, full_span);
+                for pair in inner {
+                    self.visit(pair);
+                }
+            }
+            Rule::Include => {
+                if let Some(path) = pair.into_inner().next() {
+                    let path = Include { path: path.as_str().trim_matches('"').to_string(), span: span(&path) };
+                    self.model.includes.push(path);
+                }
+            }
+            _ => {
+                for pair in pair.into_inner() {
+                    self.visit(pair);
+                }
+            }
+        }
+    }
+}
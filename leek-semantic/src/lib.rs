@@ -0,0 +1,434 @@
+//! Semantic model of LeekScript files: scopes, declarations, and the
+//! declaration each identifier refers to.
+//!
+//! ```
+//! let model = leek_semantic::analyze("var life = 1;\nlife = getLife();").unwrap();
+//! let reference = model.reference_at(15).unwrap();
+//! let declaration = model.declaration(model.definition(reference).unwrap());
+//! assert_eq!(declaration.name, "life");
+//! ```
+//!
+//! Names are resolved within the file first. Globals, functions and
+//! classes of the included files are then looked up with
+//! [`SemanticModel::link`]; what is still unresolved may be a built-in.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use leek_parser::SyntaxError;
+
+mod builder;
+
+pub type ScopeId = usize;
+pub type DeclarationId = usize;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScopeKind {
+    File,
+    Function,
+    /// Body of an `if` or `else`, or a bare block.
+    Block,
+    /// A `while` or `for` loop, with the variables of its header.
+    Loop,
+    Class,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub span: Range<usize>,
+    /// Named functions and methods do not see the local variables of the
+    /// enclosing scopes, only the globals, functions and classes.
+    pub isolated: bool,
+    pub declarations: Vec<DeclarationId>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DeclarationKind {
+    /// `var`
+    Variable,
+    /// `global`
+    Global,
+    Parameter,
+    Function,
+    Class,
+    Field,
+    Method,
+}
+
+impl DeclarationKind {
+    /// Whether the declaration is visible from the whole file, from the
+    /// functions it declares and from the files including it.
+    pub fn is_global(self) -> bool {
+        matches!(self, DeclarationKind::Global | DeclarationKind::Function | DeclarationKind::Class)
+    }
+
+    /// Human readable name, used in messages.
+    pub fn describe(self) -> &'static str {
+        match self {
+            DeclarationKind::Variable => "variable",
+            DeclarationKind::Global => "global",
+            DeclarationKind::Parameter => "parameter",
+            DeclarationKind::Function => "function",
+            DeclarationKind::Class => "class",
+            DeclarationKind::Field => "field",
+            DeclarationKind::Method => "method",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Declaration {
+    pub name: String,
+    pub kind: DeclarationKind,
+    pub scope: ScopeId,
+    /// Span of the name.
+    pub span: Range<usize>,
+    /// Span of the whole declaration.
+    pub full_span: Range<usize>,
+    /// Names of the parameters of functions and methods.
+    pub parameters: Vec<String>,
+    /// Number of parameters which must be given, the next ones having a
+    /// default value.
+    pub required_parameters: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Read,
+    /// Left-hand side of an assignment.
+    Write,
+    Call,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Declaration(DeclarationId),
+    /// A declaration of an included file, as given to
+    /// [`SemanticModel::link`].
+    Included { file: String, declaration: DeclarationId },
+    Unresolved,
+}
+
+/// A use of a name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    pub name: String,
+    pub kind: ReferenceKind,
+    /// Innermost scope of the use.
+    pub scope: ScopeId,
+    pub span: Range<usize>,
+    pub resolution: Resolution,
+    /// Number of arguments of a call.
+    pub arguments: Option<usize>,
+}
+
+/// An `include` statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Include {
+    /// Path of the included file, as written.
+    pub path: String,
+    /// Span of the path, quotes included.
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SemanticModel {
+    scopes: Vec<Scope>,
+    declarations: Vec<Declaration>,
+    references: Vec<Reference>,
+    includes: Vec<Include>,
+}
+
+/// Build the semantic model of a whole file.
+pub fn analyze(source: &str) -> Result<SemanticModel, SyntaxError> {
+    let pairs = leek_parser::parse(source)?;
+    let mut model = builder::build(source, pairs);
+    for i in 0..model.references.len() {
+        let resolution = match model.resolve(&model.references[i]) {
+            Some(declaration) => Resolution::Declaration(declaration),
+            None => Resolution::Unresolved,
+        };
+        model.references[i].resolution = resolution;
+    }
+    Ok(model)
+}
+
+fn contains(span: &Range<usize>, offset: usize) -> bool {
+    span.start <= offset && offset <= span.end
+}
+
+impl SemanticModel {
+    /// The scope of the whole file.
+    pub const FILE: ScopeId = 0;
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id]
+    }
+
+    pub fn declarations(&self) -> &[Declaration] {
+        &self.declarations
+    }
+
+    pub fn declaration(&self, id: DeclarationId) -> &Declaration {
+        &self.declarations[id]
+    }
+
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    pub fn includes(&self) -> &[Include] {
+        &self.includes
+    }
+
+    /// Declarations visible from the files including this one.
+    pub fn exports(&self) -> impl Iterator<Item = (DeclarationId, &Declaration)> {
+        self.scopes[Self::FILE]
+            .declarations
+            .iter()
+            .map(move |&id| (id, &self.declarations[id]))
+            .filter(|(_, declaration)| declaration.kind.is_global())
+    }
+
+    /// Resolve the names still unresolved with the exports of the included
+    /// files, identified by `file`, in the order of precedence.
+    pub fn link<'a, I>(&mut self, included: I)
+    where
+        I: IntoIterator<Item = (&'a str, &'a SemanticModel)>,
+    {
+        let included: Vec<_> = included.into_iter().collect();
+        for reference in &mut self.references {
+            if reference.resolution != Resolution::Unresolved {
+                continue;
+            }
+            let found = included.iter().find_map(|(file, model)| {
+                model.exports().find(|(_, declaration)| declaration.name == reference.name).map(|(id, _)| (file, id))
+            });
+            if let Some((file, declaration)) = found {
+                reference.resolution = Resolution::Included { file: file.to_string(), declaration };
+            }
+        }
+    }
+
+    /// The declaration of this file `reference` refers to.
+    pub fn definition(&self, reference: &Reference) -> Option<DeclarationId> {
+        match reference.resolution {
+            Resolution::Declaration(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Uses of the declaration `id`.
+    pub fn references_to(&self, id: DeclarationId) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(move |reference| reference.resolution == Resolution::Declaration(id))
+    }
+
+    /// The reference whose name is at `offset`.
+    pub fn reference_at(&self, offset: usize) -> Option<&Reference> {
+        self.references.iter().find(|reference| contains(&reference.span, offset))
+    }
+
+    /// The declaration whose name is at `offset`.
+    pub fn declaration_at(&self, offset: usize) -> Option<DeclarationId> {
+        self.declarations.iter().position(|declaration| contains(&declaration.span, offset))
+    }
+
+    /// Innermost scope containing `offset`.
+    pub fn scope_at(&self, offset: usize) -> ScopeId {
+        // Scopes are created before the scopes they contain.
+        (0..self.scopes.len())
+            .rev()
+            .find(|&id| contains(&self.scopes[id].span, offset))
+            .unwrap_or(Self::FILE)
+    }
+
+    /// Declarations visible at `offset`, innermost first, without the ones
+    /// they shadow.
+    pub fn visible_at(&self, offset: usize) -> Vec<DeclarationId> {
+        let mut names = HashSet::new();
+        let mut visible = Vec::new();
+        self.walk_up(self.scope_at(offset), offset, |id, declaration| {
+            if names.insert(declaration.name.as_str()) {
+                visible.push(id);
+            }
+            false
+        });
+        visible
+    }
+
+    /// Declaration of the name of `reference` in this file.
+    fn resolve(&self, reference: &Reference) -> Option<DeclarationId> {
+        let mut found = None;
+        self.walk_up(reference.scope, reference.span.start, |id, declaration| {
+            if declaration.name == reference.name {
+                found = Some(id);
+            }
+            found.is_some()
+        });
+        found
+    }
+
+    /// Call `visit` with the declarations visible at `offset` in `scope`,
+    /// from the innermost scope to the file, latest declarations first,
+    /// until it returns `true`.
+    fn walk_up<'a, F>(&'a self, scope: ScopeId, offset: usize, mut visit: F)
+    where
+        F: FnMut(DeclarationId, &'a Declaration) -> bool,
+    {
+        let mut current = Some(scope);
+        let mut isolated = false;
+        while let Some(id) = current {
+            let scope = &self.scopes[id];
+            for &declaration in scope.declarations.iter().rev() {
+                let visible = match self.declarations[declaration].kind {
+                    DeclarationKind::Variable | DeclarationKind::Parameter => {
+                        // Local variables exist once their declaration is
+                        // complete.
+                        !isolated && self.declarations[declaration].full_span.end <= offset
+                    }
+                    _ => true,
+                };
+                if visible && visit(declaration, &self.declarations[declaration]) {
+                    return;
+                }
+            }
+            isolated |= scope.isolated;
+            current = scope.parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Name of the declaration the name at the `n`th occurrence of `text`
+    /// refers to, with its kind and the line of its declaration.
+    fn resolve_at(model: &SemanticModel, source: &str, text: &str, n: usize) -> Option<(DeclarationKind, usize)> {
+        let offset = source.match_indices(text).nth(n).unwrap().0;
+        let reference = model.reference_at(offset).unwrap();
+        let declaration = model.declaration(model.definition(reference)?);
+        Some((declaration.kind, source[..declaration.span.start].matches('\n').count()))
+    }
+
+    #[test]
+    fn resolve_names() {
+        let source = "\
+var count = 1;
+global total = count;
+function add(count) {
+    var sum = count;
+    return helper(total, sum, later);
+}
+function helper(a) { return count; }
+var later = 2;
+";
+        let model = analyze(source).unwrap();
+        assert_eq!(model.scopes().len(), 3);
+        assert_eq!(resolve_at(&model, source, "count", 1), Some((DeclarationKind::Variable, 0)));
+        // The parameter shadows the variable.
+        assert_eq!(resolve_at(&model, source, "count", 3), Some((DeclarationKind::Parameter, 2)));
+        // Functions are hoisted, globals are visible from functions.
+        assert_eq!(resolve_at(&model, source, "helper", 0), Some((DeclarationKind::Function, 6)));
+        assert_eq!(resolve_at(&model, source, "total", 1), Some((DeclarationKind::Global, 1)));
+        // Named functions do not see the local variables around them, and
+        // variables do not exist before their declaration.
+        assert_eq!(resolve_at(&model, source, "count", 4), None);
+        assert_eq!(resolve_at(&model, source, "later", 0), None);
+
+        let helper = model.declaration_at(source.find("helper(a)").unwrap()).unwrap();
+        assert_eq!(model.declaration(helper).parameters, ["a"]);
+        assert_eq!(model.references_to(helper).count(), 1);
+        assert_eq!(model.references_to(helper).next().unwrap().kind, ReferenceKind::Call);
+    }
+
+    #[test]
+    fn default_parameters_and_arguments() {
+        let source = "\
+global mp = 1;
+function attack(target, range = target, mp = mp) { return target; }
+attack(1, attack(2));";
+        let model = analyze(source).unwrap();
+        let attack = model.declaration(model.declaration_at(source.find("attack").unwrap()).unwrap());
+        assert_eq!(attack.parameters, ["target", "range", "mp"]);
+        assert_eq!(attack.required_parameters, 1);
+        // Default values are computed by the function, they see the
+        // parameters before theirs.
+        assert_eq!(resolve_at(&model, source, "target", 1), Some((DeclarationKind::Parameter, 1)));
+        assert_eq!(resolve_at(&model, source, "mp", 2), Some((DeclarationKind::Global, 0)));
+        let arguments: Vec<_> = model.references().iter().map(|reference| reference.arguments).collect();
+        assert_eq!(arguments, [None, None, None, Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn blocks_loops_and_classes() {
+        let source = "\
+var items = 1;
+for (var item in items) { var inner = item; }
+if (items) { var inner = 2; inner = 3; } else { inner = 4; }
+while (items) { var step = 1; { var deep = step; } deep = 2; }
+var callback = function(x) { return items; };
+class Leek extends Entity {
+    life = 10;
+    heal(amount) { return life; }
+}
+";
+        let model = analyze(source).unwrap();
+        let kinds: Vec<_> = model.scopes().iter().map(|scope| scope.kind).collect();
+        assert_eq!(kinds, [
+            ScopeKind::File,
+            ScopeKind::Loop,
+            ScopeKind::Block,
+            ScopeKind::Block,
+            ScopeKind::Loop,
+            ScopeKind::Block,
+            ScopeKind::Function,
+            ScopeKind::Class,
+            ScopeKind::Function,
+        ]);
+        assert_eq!(resolve_at(&model, source, "item", 3), Some((DeclarationKind::Variable, 1)));
+        assert_eq!(resolve_at(&model, source, "inner", 2), Some((DeclarationKind::Variable, 2)));
+        // Each branch has its own scope.
+        assert_eq!(resolve_at(&model, source, "inner", 3), None);
+        assert_eq!(resolve_at(&model, source, "deep", 1), None);
+        // Anonymous functions see the variables around them.
+        assert_eq!(resolve_at(&model, source, "items", 4), Some((DeclarationKind::Variable, 0)));
+        assert_eq!(resolve_at(&model, source, "life", 1), Some((DeclarationKind::Field, 6)));
+        assert_eq!(resolve_at(&model, source, "Entity", 0), None);
+
+        let heal = source.find("heal").unwrap();
+        assert_eq!(model.declaration(model.declaration_at(heal).unwrap()).kind, DeclarationKind::Method);
+        let names: Vec<_> = model
+            .visible_at(source.find("return life").unwrap())
+            .into_iter()
+            .map(|id| model.declaration(id).name.as_str())
+            .collect();
+        assert_eq!(names, ["amount", "heal", "life", "Leek"]);
+    }
+
+    #[test]
+    fn link_included_files() {
+        let library = analyze("global level = 1;\nvar local = 2;\nfunction attack() { return level; }").unwrap();
+        let exports: Vec<_> = library.exports().map(|(_, declaration)| declaration.name.as_str()).collect();
+        assert_eq!(exports, ["level", "attack"]);
+
+        let source = "include(\"lib.leek\");\nattack(local, getLife());";
+        let mut model = analyze(source).unwrap();
+        assert_eq!(model.includes(), [Include { path: "lib.leek".to_string(), span: 8..18 }]);
+        model.link(vec![("lib.leek", &library)]);
+        let resolutions: Vec<_> = model.references().iter().map(|reference| &reference.resolution).collect();
+        assert_eq!(resolutions, [
+            &Resolution::Included { file: "lib.leek".to_string(), declaration: 2 },
+            &Resolution::Unresolved,
+            &Resolution::Unresolved,
+        ]);
+    }
+}